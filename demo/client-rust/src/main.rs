@@ -178,9 +178,11 @@ fn main() {
     // Step 4: 双向加密通信测试
     println!("\n--- Step 4: Bidirectional Crypto Test ---");
 
-    // 初始化SM4
-    let iv = hex_to_bytes("00000000000000000000000000000000");
+    // 初始化SM4：服务端协议固定使用全零IV（见 CryptoHandler），此处必须显式传入；
+    // 每个会话协商出新的Ka，但同一会话内的消息共用该IV，仅用于演示互通
+    let iv = [0u8; 16];
     let ka_bytes = hex_to_bytes(ka);
+    assert_eq!(ka_bytes.len(), 16, "negotiated key must be 16 bytes");
     let mut sm4 = SM4::new();
     sm4.set_key(&ka_bytes, &iv).expect("Failed to set SM4 key");
 
@@ -0,0 +1,74 @@
+// SM3 吞吐量测试（无第三方依赖）
+// 运行: cargo bench --bench sm3
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use gm_rust::sm3::{Sm3, sm3_many};
+
+fn bench(name: &str, bytes: usize, mut f: impl FnMut()) {
+    // 预热
+    f();
+    let rounds = 10;
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    let secs = start.elapsed().as_secs_f64();
+    let mib = (bytes * rounds) as f64 / (1024.0 * 1024.0);
+    println!("{:<36} {:>10.2} MiB/s", name, mib / secs);
+}
+
+fn main() {
+    let data = vec![0x5au8; 4 << 20];
+
+    bench("update_byte loop (4 MiB)", data.len(), || {
+        let mut sm3 = Sm3::new();
+        for &b in black_box(&data) {
+            sm3.update_byte(b);
+        }
+        black_box(sm3.finish().hash_bytes());
+    });
+
+    bench("update block path (4 MiB)", data.len(), || {
+        let mut sm3 = Sm3::new();
+        black_box(sm3.update(black_box(&data)).finish().hash_bytes());
+    });
+
+    // 短消息：SM2 签名中的 Z 值与 e 值计算规模
+    let short = [0x5au8; 64];
+    let count = 100_000;
+    bench("64-byte messages, bytes only", short.len() * count, || {
+        for _ in 0..count {
+            let mut sm3 = Sm3::new();
+            black_box(sm3.update(black_box(&short)).finish().hash_bytes());
+        }
+    });
+
+    bench("64-byte messages, with hex", short.len() * count, || {
+        for _ in 0..count {
+            let mut sm3 = Sm3::new();
+            black_box(sm3.update(black_box(&short)).finish().hash_hex_upper().len());
+        }
+    });
+
+    // 多消息并行：去重场景的 4 KiB 分块
+    let chunks: Vec<Vec<u8>> = (0..1024).map(|i| vec![i as u8; 4096]).collect();
+    let messages: Vec<&[u8]> = chunks.iter().map(|c| c.as_slice()).collect();
+    let total = 4096 * messages.len();
+    bench("4 KiB chunks, one at a time", total, || {
+        for m in &messages {
+            let mut sm3 = Sm3::new();
+            black_box(sm3.update(black_box(m)).finalize());
+        }
+    });
+
+    bench("4 KiB chunks, sm3_many", total, || {
+        black_box(sm3_many(black_box(&messages)));
+    });
+
+    let small: Vec<&[u8]> = (0..count).map(|_| &short[..]).collect();
+    bench("64-byte messages, sm3_many", short.len() * count, || {
+        black_box(sm3_many(black_box(&small)));
+    });
+}
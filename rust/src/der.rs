@@ -0,0 +1,420 @@
+// crate 内部使用的最小 DER 编解码，只支持单字节标签与确定长度编码
+
+use std::error::Error;
+use std::fmt;
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const NULL: u8 = 0x05;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const ENUMERATED: u8 = 0x0a;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+
+/// 上下文相关的构造类型标签 [n]
+pub(crate) const fn context(n: u8) -> u8 {
+    0xa0 | n
+}
+
+/// DER 编码错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DerError;
+
+impl fmt::Display for DerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Malformed DER encoding")
+    }
+}
+
+impl Error for DerError {}
+
+/// 常用对象标识符（DER 编码后的内容字节）
+pub(crate) mod oid {
+    /// id-ecPublicKey 1.2.840.10045.2.1
+    pub(crate) const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+    /// sm2p256v1 1.2.156.10197.1.301
+    pub(crate) const SM2P256V1: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d];
+    /// sm2-1 椭圆曲线数字签名算法 1.2.156.10197.1.301.1
+    pub(crate) const SM2_SIGN: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d, 0x01];
+    /// sm2-3 椭圆曲线公钥加密算法 1.2.156.10197.1.301.3
+    pub(crate) const SM2_ENCRYPT: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d, 0x03];
+    /// SM3 1.2.156.10197.1.401
+    pub(crate) const SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x11];
+    /// SM2-with-SM3 1.2.156.10197.1.501
+    pub(crate) const SM2_WITH_SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x75];
+    /// SM4-CBC 1.2.156.10197.1.104.2
+    pub(crate) const SM4_CBC: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x68, 0x02];
+    /// HMAC-SM3 1.2.156.10197.1.401.2（GM/T 0006，GmSSL 使用）
+    pub(crate) const HMAC_SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x11, 0x02];
+    /// hmacWithSM3 1.2.156.10197.1.401.3.1（OpenSSL 使用）
+    pub(crate) const HMAC_WITH_SM3: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x11, 0x03, 0x01];
+    /// id-PBES2 1.2.840.113549.1.5.13
+    pub(crate) const PBES2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+    /// id-PBKDF2 1.2.840.113549.1.5.12
+    pub(crate) const PBKDF2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+}
+
+/// 顺序读取 TLV
+#[derive(Clone, Copy)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub(crate) fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// 读取完整的 TLV，返回 (标签, 内容, 整个TLV)
+    fn read_tlv(&mut self) -> Result<(u8, &'a [u8], &'a [u8]), DerError> {
+        let data = self.data;
+        let (&tag, rest) = data.split_first().ok_or(DerError)?;
+        // 不支持高标签号
+        if tag & 0x1f == 0x1f {
+            return Err(DerError);
+        }
+        let (&first, rest) = rest.split_first().ok_or(DerError)?;
+        let (len, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            // 长格式：不允许不定长、前导零，以及可用短格式表示的长度
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > 4 || rest.len() < n || rest[0] == 0 {
+                return Err(DerError);
+            }
+            let len = rest[..n].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+            if len < 0x80 {
+                return Err(DerError);
+            }
+            (len, &rest[n..])
+        };
+        if rest.len() < len {
+            return Err(DerError);
+        }
+        let header = data.len() - rest.len();
+        self.data = &rest[len..];
+        Ok((tag, &rest[..len], &data[..header + len]))
+    }
+
+    /// 读取任意标签的元素，返回 (标签, 内容)
+    pub(crate) fn read_any(&mut self) -> Result<(u8, &'a [u8]), DerError> {
+        self.read_tlv().map(|(tag, content, _)| (tag, content))
+    }
+
+    /// 读取指定标签的元素，返回包括标签与长度在内的完整编码
+    pub(crate) fn read_raw(&mut self, tag: u8) -> Result<&'a [u8], DerError> {
+        match self.read_tlv()? {
+            (t, _, raw) if t == tag => Ok(raw),
+            _ => Err(DerError),
+        }
+    }
+
+    /// 读取指定标签的元素内容
+    pub(crate) fn read(&mut self, tag: u8) -> Result<&'a [u8], DerError> {
+        match self.read_tlv()? {
+            (t, content, _) if t == tag => Ok(content),
+            _ => Err(DerError),
+        }
+    }
+
+    /// 下一个元素标签相符时读取，否则不消耗数据
+    pub(crate) fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>, DerError> {
+        if self.peek_tag() == Some(tag) { self.read(tag).map(Some) } else { Ok(None) }
+    }
+
+    pub(crate) fn sequence(&mut self) -> Result<Reader<'a>, DerError> {
+        self.read(SEQUENCE).map(Reader::new)
+    }
+
+    /// 非负 INTEGER，返回去掉符号位填充后的大端字节（零为空）
+    pub(crate) fn uint(&mut self) -> Result<&'a [u8], DerError> {
+        let content = self.read(INTEGER)?;
+        match content {
+            [] => Err(DerError),
+            [b, ..] if b & 0x80 != 0 => Err(DerError),
+            [0, b, ..] if b & 0x80 == 0 => Err(DerError),
+            [0, rest @ ..] => Ok(rest),
+            _ => Ok(content),
+        }
+    }
+
+    /// 不超过64位的非负 INTEGER
+    pub(crate) fn small_uint(&mut self) -> Result<u64, DerError> {
+        let bytes = self.uint()?;
+        if bytes.len() > 8 {
+            return Err(DerError);
+        }
+        Ok(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    /// DER 中 TRUE 只能编码为 0xff
+    pub(crate) fn boolean(&mut self) -> Result<bool, DerError> {
+        match self.read(BOOLEAN)? {
+            [0x00] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(DerError),
+        }
+    }
+
+    pub(crate) fn oid(&mut self) -> Result<&'a [u8], DerError> {
+        self.read(OID)
+    }
+
+    pub(crate) fn octet_string(&mut self) -> Result<&'a [u8], DerError> {
+        self.read(OCTET_STRING)
+    }
+
+    /// 读取可选的 NULL，常见于 AlgorithmIdentifier 的参数
+    pub(crate) fn optional_null(&mut self) -> Result<(), DerError> {
+        match self.read_optional(NULL)? {
+            Some([_, ..]) => Err(DerError),
+            _ => Ok(()),
+        }
+    }
+
+    /// 只接受未用位数为0的 BIT STRING
+    pub(crate) fn bit_string(&mut self) -> Result<&'a [u8], DerError> {
+        match self.read(BIT_STRING)? {
+            [0, rest @ ..] => Ok(rest),
+            _ => Err(DerError),
+        }
+    }
+
+    /// 确认所有数据已读完
+    pub(crate) fn finish(self) -> Result<(), DerError> {
+        if self.is_empty() { Ok(()) } else { Err(DerError) }
+    }
+}
+
+/// OID 内容字节转为点分形式
+pub(crate) fn oid_to_string(content: &[u8]) -> Result<String, DerError> {
+    let mut arcs = Vec::new();
+    let mut v: u64 = 0;
+    for (i, &b) in content.iter().enumerate() {
+        // 每个分量不得有 0x80 前导字节，且不超过64位
+        if (v == 0 && b == 0x80) || v >> 57 != 0 {
+            return Err(DerError);
+        }
+        v = (v << 7) | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (v / 40).min(2);
+                arcs.push(first);
+                arcs.push(v - first * 40);
+            } else {
+                arcs.push(v);
+            }
+            v = 0;
+        } else if i == content.len() - 1 {
+            return Err(DerError);
+        }
+    }
+    if arcs.is_empty() {
+        return Err(DerError);
+    }
+    Ok(arcs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join("."))
+}
+
+/// 点分形式转为 OID 内容字节
+pub(crate) fn oid_from_str(s: &str) -> Option<Vec<u8>> {
+    let arcs: Vec<u64> = s.split('.').map(|a| a.parse().ok()).collect::<Option<_>>()?;
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+        return None;
+    }
+    let first = arcs[0].checked_mul(40)?.checked_add(arcs[1])?;
+    let mut out = Vec::new();
+    for mut v in std::iter::once(first).chain(arcs[2..].iter().copied()) {
+        let mut tmp = vec![(v & 0x7f) as u8];
+        v >>= 7;
+        while v > 0 {
+            tmp.push(0x80 | (v & 0x7f) as u8);
+            v >>= 7;
+        }
+        out.extend(tmp.iter().rev());
+    }
+    Some(out)
+}
+
+/// 编码一个 TLV
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let len = content.len();
+    let mut out = Vec::with_capacity(len + 6);
+    out.push(tag);
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = (len as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (4 - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// 依次拼接各部分后编码为指定标签的构造类型
+pub(crate) fn constructed(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+    tlv(tag, &parts.concat())
+}
+
+pub(crate) fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    constructed(SEQUENCE, parts)
+}
+
+/// 大端字节表示的非负 INTEGER，自动去掉多余前导零并补符号位
+pub(crate) fn uint(bytes: &[u8]) -> Vec<u8> {
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    let bytes = &bytes[skip..];
+    match bytes.first() {
+        None => tlv(INTEGER, &[0]),
+        Some(b) if b & 0x80 != 0 => tlv(INTEGER, &[&[0], bytes].concat()),
+        Some(_) => tlv(INTEGER, bytes),
+    }
+}
+
+pub(crate) fn small_uint(v: u64) -> Vec<u8> {
+    uint(&v.to_be_bytes())
+}
+
+pub(crate) fn oid(content: &[u8]) -> Vec<u8> {
+    tlv(OID, content)
+}
+
+pub(crate) fn octet_string(content: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, content)
+}
+
+pub(crate) fn boolean(v: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if v { 0xff } else { 0x00 }])
+}
+
+pub(crate) fn null() -> Vec<u8> {
+    tlv(NULL, &[])
+}
+
+pub(crate) fn bit_string(content: &[u8]) -> Vec<u8> {
+    tlv(BIT_STRING, &[&[0], content].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 按点分形式编码OID内容，用于核对常量
+    fn encode_oid(arcs: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut push = |mut v: u64| {
+            let mut tmp = vec![(v & 0x7f) as u8];
+            v >>= 7;
+            while v > 0 {
+                tmp.push(0x80 | (v & 0x7f) as u8);
+                v >>= 7;
+            }
+            out.extend(tmp.iter().rev());
+        };
+        push(arcs[0] * 40 + arcs[1]);
+        for &arc in &arcs[2..] {
+            push(arc);
+        }
+        out
+    }
+
+    #[test]
+    fn test_oid_constants() {
+        assert_eq!(oid::EC_PUBLIC_KEY, encode_oid(&[1, 2, 840, 10045, 2, 1]));
+        assert_eq!(oid::SM2P256V1, encode_oid(&[1, 2, 156, 10197, 1, 301]));
+        assert_eq!(oid::SM2_SIGN, encode_oid(&[1, 2, 156, 10197, 1, 301, 1]));
+        assert_eq!(oid::SM2_ENCRYPT, encode_oid(&[1, 2, 156, 10197, 1, 301, 3]));
+        assert_eq!(oid::SM3, encode_oid(&[1, 2, 156, 10197, 1, 401]));
+        assert_eq!(oid::SM2_WITH_SM3, encode_oid(&[1, 2, 156, 10197, 1, 501]));
+        assert_eq!(oid::SM4_CBC, encode_oid(&[1, 2, 156, 10197, 1, 104, 2]));
+        assert_eq!(oid::HMAC_SM3, encode_oid(&[1, 2, 156, 10197, 1, 401, 2]));
+        assert_eq!(oid::HMAC_WITH_SM3, encode_oid(&[1, 2, 156, 10197, 1, 401, 3, 1]));
+        assert_eq!(oid::PBES2, encode_oid(&[1, 2, 840, 113549, 1, 5, 13]));
+        assert_eq!(oid::PBKDF2, encode_oid(&[1, 2, 840, 113549, 1, 5, 12]));
+    }
+
+    #[test]
+    fn test_oid_to_string() {
+        assert_eq!(oid_to_string(oid::SM2P256V1).unwrap(), "1.2.156.10197.1.301");
+        assert_eq!(oid_to_string(&encode_oid(&[2, 999, 3])).unwrap(), "2.999.3");
+        assert_eq!(oid_to_string(&encode_oid(&[0, 39, u64::MAX])).unwrap(), format!("0.39.{}", u64::MAX));
+        // 空、截断、非最短编码
+        for bad in [&[][..], &[0x2a, 0x81], &[0x2a, 0x80, 0x01]] {
+            assert_eq!(oid_to_string(bad), Err(DerError));
+        }
+
+        assert_eq!(oid_from_str("1.2.156.10197.1.301").unwrap(), oid::SM2P256V1);
+        assert_eq!(oid_from_str("2.999.3").unwrap(), encode_oid(&[2, 999, 3]));
+        for bad in ["", "1", "3.1", "1.40", "1.2.x", "1..2"] {
+            assert_eq!(oid_from_str(bad), None);
+        }
+    }
+
+    #[test]
+    fn test_lengths() {
+        for len in [0usize, 1, 127, 128, 255, 256, 65535, 65536] {
+            let content = vec![0x5a; len];
+            let encoded = tlv(OCTET_STRING, &content);
+            let mut r = Reader::new(&encoded);
+            assert_eq!(r.octet_string().unwrap(), &content[..]);
+            r.finish().unwrap();
+        }
+        // 非最短长度、不定长、截断
+        for bad in [&[0x04, 0x81, 0x01, 0x00][..], &[0x04, 0x80, 0x00, 0x00], &[0x04, 0x82, 0x00, 0x80], &[0x04, 0x02, 0x00]] {
+            assert_eq!(Reader::new(bad).octet_string(), Err(DerError));
+        }
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(uint(&[0, 0, 0x7f]), [0x02, 0x01, 0x7f]);
+        assert_eq!(uint(&[0x80]), [0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(uint(&[]), [0x02, 0x01, 0x00]);
+        assert_eq!(small_uint(65537), [0x02, 0x03, 0x01, 0x00, 0x01]);
+
+        assert_eq!(Reader::new(&uint(&[0x80, 0x01])).uint().unwrap(), [0x80, 0x01]);
+        assert_eq!(Reader::new(&small_uint(0)).small_uint().unwrap(), 0);
+        assert_eq!(Reader::new(&small_uint(u64::MAX)).small_uint().unwrap(), u64::MAX);
+        // 负数与非最短编码
+        assert!(Reader::new(&[0x02, 0x01, 0x80]).uint().is_err());
+        assert!(Reader::new(&[0x02, 0x02, 0x00, 0x01]).uint().is_err());
+        assert!(Reader::new(&[0x02, 0x00]).uint().is_err());
+    }
+
+    #[test]
+    fn test_nested() {
+        let encoded = sequence(&[&small_uint(1), &oid(oid::SM2P256V1), &constructed(context(0), &[&bit_string(b"xy")])]);
+        let mut r = Reader::new(&encoded);
+        let mut seq = r.sequence().unwrap();
+        r.finish().unwrap();
+        assert_eq!(seq.small_uint().unwrap(), 1);
+        assert_eq!(seq.read_optional(OCTET_STRING).unwrap(), None);
+        assert_eq!(seq.oid().unwrap(), oid::SM2P256V1);
+        let mut ctx = Reader::new(seq.read(context(0)).unwrap());
+        assert_eq!(ctx.bit_string().unwrap(), b"xy");
+        assert!(seq.is_empty());
+
+        let encoded = [boolean(true), boolean(false), vec![0x01, 0x01, 0x01]].concat();
+        let mut r = Reader::new(&encoded);
+        assert!(r.boolean().unwrap());
+        assert_eq!(r.read_raw(BOOLEAN).unwrap(), [0x01, 0x01, 0x00]);
+        assert_eq!(r.read_any().unwrap(), (BOOLEAN, &[0x01][..]));
+        assert!(r.is_empty());
+        assert!(Reader::new(&[0x01, 0x01, 0x01]).boolean().is_err());
+
+        let mut r = Reader::new(&[0x05, 0x00, 0x05, 0x01, 0x00]);
+        r.optional_null().unwrap();
+        r.optional_null().unwrap_err();
+    }
+}
@@ -0,0 +1,193 @@
+//! 密钥派生函数
+//! - `sm3_kdf`：GB/T 32918.3 / GM/T 0003 中基于SM3的KDF，SM2加密与密钥交换均使用它
+//! - `Hkdf`：HKDF-SM3（RFC 5869），可从一次密钥交换的输出派生多组用途不同的密钥与IV
+
+use std::error::Error;
+use std::fmt;
+
+use crate::hmac::HmacSm3;
+use crate::sm3::Sm3;
+
+/// SM3 输出长度（字节）
+const HASH_LEN: usize = 32;
+
+/// KDF相关错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KdfError {
+    InvalidOutputLength,
+    InvalidPrkLength,
+}
+
+impl fmt::Display for KdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KdfError::InvalidOutputLength => write!(f, "Requested output length is too large"),
+            KdfError::InvalidPrkLength => write!(f, "Pseudorandom key must be at least 32 bytes"),
+        }
+    }
+}
+
+impl Error for KdfError {}
+
+/// GM/T KDF：`out` 依次填入 SM3(Z || ct)，ct 为从1开始的32位大端计数器
+pub fn sm3_kdf(z: &[u8], out: &mut [u8]) {
+    sm3_kdf_parts(&[z], out);
+}
+
+/// GM/T KDF，返回 `keylen` 字节
+pub fn sm3_kdf_vec(z: &[u8], keylen: usize) -> Vec<u8> {
+    let mut out = vec![0u8; keylen];
+    sm3_kdf(z, &mut out);
+    out
+}
+
+/// GM/T KDF，Z 由多段依次拼接而成，省去调用方拼接
+pub(crate) fn sm3_kdf_parts(z: &[&[u8]], out: &mut [u8]) {
+    for (block, ct) in out.chunks_mut(HASH_LEN).zip(1u32..) {
+        let mut sm3 = Sm3::new();
+        for part in z {
+            sm3.update(part);
+        }
+        sm3.update(&ct.to_be_bytes()).finish();
+        block.copy_from_slice(&sm3.hash_bytes()[..block.len()]);
+    }
+}
+
+/// HKDF-SM3
+#[derive(Clone)]
+pub struct Hkdf {
+    // 以PRK为密钥的HMAC
+    prf: HmacSm3,
+}
+
+impl Hkdf {
+    /// Extract：PRK = HMAC-SM3(salt, IKM)，`salt` 为空时使用32字节全零
+    pub fn new(salt: &[u8], ikm: &[u8]) -> Self {
+        Hkdf { prf: HmacSm3::new(&hkdf_extract(salt, ikm)) }
+    }
+
+    /// 跳过 Extract，直接使用已是均匀随机的PRK
+    pub fn from_prk(prk: &[u8]) -> Result<Self, KdfError> {
+        if prk.len() < HASH_LEN {
+            return Err(KdfError::InvalidPrkLength);
+        }
+        Ok(Hkdf { prf: HmacSm3::new(prk) })
+    }
+
+    /// Expand：按 `info` 派生 `out.len()` 字节，最多 255 * 32 字节
+    pub fn expand(&self, info: &[u8], out: &mut [u8]) -> Result<(), KdfError> {
+        if out.len() > 255 * HASH_LEN {
+            return Err(KdfError::InvalidOutputLength);
+        }
+
+        // T(i) = HMAC(PRK, T(i-1) || info || i)
+        let mut t = [0u8; HASH_LEN];
+        for (i, block) in out.chunks_mut(HASH_LEN).enumerate() {
+            let mut mac = self.prf.clone();
+            if i > 0 {
+                mac.update(&t);
+            }
+            mac.update(info).update(&[i as u8 + 1]);
+            t = mac.finalize();
+            block.copy_from_slice(&t[..block.len()]);
+        }
+        Ok(())
+    }
+}
+
+/// HKDF Extract
+pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; HASH_LEN] {
+    let zero = [0u8; HASH_LEN];
+    let salt = if salt.is_empty() { &zero[..] } else { salt };
+    let mut mac = HmacSm3::new(salt);
+    mac.update(ikm);
+    mac.finalize()
+}
+
+/// 一次性完成 Extract 与 Expand
+pub fn hkdf_sm3(salt: &[u8], ikm: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), KdfError> {
+    Hkdf::new(salt, ikm).expand(info, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    fn range(from: u8, to: u8) -> Vec<u8> {
+        (from..=to).collect()
+    }
+
+    // 以下期望值均由 OpenSSL 3 `kdf -kdfopt digest:SM3 X963KDF` 计算（无SharedInfo时与GM/T KDF相同）
+    #[test]
+    fn test_sm3_kdf_vectors() {
+        assert_eq!(
+            hex(&sm3_kdf_vec(b"abc", 32)),
+            "FE1EA80DAC6F100C33537BD24619EC7C72A1E8B1FFEAEFB1EB52A37791FDAF61"
+        );
+        assert_eq!(
+            hex(&sm3_kdf_vec(&[0x0b; 22], 45)),
+            "2E24FD46D50D6D29493A7AFBA03CF143587B5392D75A9AFEEB8EB78E3E51DB8129CE6CD069B6176BB1ABA9DF54"
+        );
+        assert!(sm3_kdf_vec(b"abc", 0).is_empty());
+
+        let mut split = [0u8; 45];
+        sm3_kdf_parts(&[&[0x0b; 10], &[0x0b; 12]], &mut split);
+        assert_eq!(split.to_vec(), sm3_kdf_vec(&[0x0b; 22], 45));
+    }
+
+    // 以下期望值均由 OpenSSL 3 `kdf -kdfopt digest:SM3 HKDF` 计算，输入取自 RFC 5869 附录A
+    #[test]
+    fn test_hkdf_vectors() {
+        let ikm = [0x0b; 22];
+        let salt = range(0x00, 0x0c);
+        let info = range(0xf0, 0xf9);
+
+        assert_eq!(
+            hex(&hkdf_extract(&salt, &ikm)),
+            "E0D6F7B0BD056327B7659F1F39AD850561FBCF4FB10FB58E88EAFA55CF7CD01E"
+        );
+        let mut okm = [0u8; 42];
+        hkdf_sm3(&salt, &ikm, &info, &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "C69FE91B7AAEE2DD5718D72DCAEE0CCE93F1B8E41F792DA51261B6A517E68B36ED2C595572B01DFA359B"
+        );
+
+        // 空盐与空info
+        hkdf_sm3(b"", &ikm, b"", &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "C8C91A38AE2FB3B023A7C38CE9F0748F28230D59B6B950BA3BA949BF0D713A5774815778801741CB2034"
+        );
+
+        // 较长输入
+        let mut okm = [0u8; 100];
+        hkdf_sm3(&range(0x60, 0xaf), &range(0x00, 0x4f), &range(0xb0, 0xff), &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "C1226236BBDEFA7921F9FEBE27B864F33E449201B436D8844EA53F58170DD6426DEFBD22ED1F3C5960F35523E62E3B6C\
+             0D657F2C61893436F539013199BFAEF25AAFD1E7726EDE927623A9F5CBB8885C7E5D47FF4B80851E278C175DF087BCEFEAD18340"
+        );
+    }
+
+    #[test]
+    fn test_hkdf_limits() {
+        let hkdf = Hkdf::new(b"salt", b"ikm");
+        let mut max = vec![0u8; 255 * 32];
+        assert!(hkdf.expand(b"", &mut max).is_ok());
+        let mut over = vec![0u8; 255 * 32 + 1];
+        assert_eq!(hkdf.expand(b"", &mut over), Err(KdfError::InvalidOutputLength));
+
+        assert_eq!(Hkdf::from_prk(&[0u8; 16]).err(), Some(KdfError::InvalidPrkLength));
+        let prk = hkdf_extract(b"salt", b"ikm");
+        let mut a = [0u8; 16];
+        let mut b = [0u8; 16];
+        hkdf.expand(b"sm4 key", &mut a).unwrap();
+        Hkdf::from_prk(&prk).unwrap().expand(b"sm4 key", &mut b).unwrap();
+        assert_eq!(a, b);
+    }
+}
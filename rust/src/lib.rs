@@ -1,3 +1,12 @@
+pub mod hmac;
+pub mod kdf;
+pub mod pbkdf2;
+pub mod pkcs7;
 pub mod sm2;
 pub mod sm3;
-pub mod sm4;
\ No newline at end of file
+pub mod sm4;
+pub mod x509;
+
+mod der;
+mod pem;
+mod util;
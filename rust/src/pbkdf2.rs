@@ -0,0 +1,257 @@
+//! PBKDF2-HMAC-SM3（RFC 8018）与 PHC 格式的口令哈希字符串
+//!
+//! 字符串格式：`$pbkdf2-sm3$i=<迭代次数>$<盐>$<哈希>`，盐与哈希均为不带填充的标准Base64，
+//! 输出长度即哈希解码后的长度。
+//!
+//! 校验时迭代次数与哈希长度都来自存储的字符串，分别限制为 [`MAX_ITERATIONS`] 与 [`MAX_OUTPUT_LEN`]，
+//! 以免构造的字符串长时间占用CPU。
+
+use std::error::Error;
+use std::fmt;
+
+use crate::hmac::HmacSm3;
+use crate::util::{base64_decode, base64_encode, ct_eq, getrandom};
+
+/// PHC 字符串中的算法标识
+pub const ALGORITHM_ID: &str = "pbkdf2-sm3";
+
+/// 口令哈希与校验允许的最大迭代次数，约为默认值的100倍
+pub const MAX_ITERATIONS: u32 = 10_000_000;
+
+/// PHC 字符串中哈希的最大长度（字节）
+pub const MAX_OUTPUT_LEN: usize = 64;
+
+/// HMAC-SM3 输出长度（字节）
+const PRF_LEN: usize = 32;
+
+/// PBKDF2相关错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pbkdf2Error {
+    InvalidIterations,
+    InvalidOutputLength,
+    InvalidFormat,
+    UnsupportedAlgorithm,
+    /// 迭代次数超过允许的上限
+    TooManyIterations,
+    /// 系统随机数生成器不可用
+    RandomUnavailable,
+}
+
+impl fmt::Display for Pbkdf2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pbkdf2Error::InvalidIterations => write!(f, "Iteration count must be at least 1"),
+            Pbkdf2Error::InvalidOutputLength => write!(f, "Invalid derived key length"),
+            Pbkdf2Error::InvalidFormat => write!(f, "Malformed password hash string"),
+            Pbkdf2Error::UnsupportedAlgorithm => write!(f, "Unsupported password hash algorithm"),
+            Pbkdf2Error::TooManyIterations => write!(f, "Iteration count exceeds the allowed maximum"),
+            Pbkdf2Error::RandomUnavailable => write!(f, "Secure random number generator unavailable"),
+        }
+    }
+}
+
+impl Error for Pbkdf2Error {}
+
+/// 口令哈希参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// 迭代次数
+    pub iterations: u32,
+    /// 派生密钥长度（字节）
+    pub output_len: usize,
+    /// 随机盐长度（字节）
+    pub salt_len: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { iterations: 100_000, output_len: 32, salt_len: 16 }
+    }
+}
+
+/// PBKDF2-HMAC-SM3，派生密钥写入 `out`，长度由 `out` 决定
+pub fn pbkdf2_hmac_sm3(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) -> Result<(), Pbkdf2Error> {
+    if iterations == 0 {
+        return Err(Pbkdf2Error::InvalidIterations);
+    }
+    // RFC 8018 限制 dkLen ≤ (2^32 - 1) * hLen
+    if out.is_empty() || out.len().div_ceil(PRF_LEN) as u64 > u32::MAX as u64 {
+        return Err(Pbkdf2Error::InvalidOutputLength);
+    }
+
+    // 口令只做一次密钥处理，每次迭代克隆
+    let prf = HmacSm3::new(password);
+    for (block, i) in out.chunks_mut(PRF_LEN).zip(1u32..) {
+        // U_1 = PRF(P, S || INT(i))
+        let mut mac = prf.clone();
+        mac.update(salt).update(&i.to_be_bytes());
+        let mut u = mac.finalize();
+        let mut t = u;
+        // U_j = PRF(P, U_{j-1})，T_i = U_1 ^ ... ^ U_c
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for k in 0..PRF_LEN {
+                t[k] ^= u[k];
+            }
+        }
+        block.copy_from_slice(&t[..block.len()]);
+    }
+    Ok(())
+}
+
+/// 使用随机盐计算口令哈希，返回 PHC 格式字符串
+pub fn hash_password(password: &[u8], params: &Params) -> Result<String, Pbkdf2Error> {
+    let mut salt = vec![0u8; params.salt_len];
+    getrandom(&mut salt).map_err(|_| Pbkdf2Error::RandomUnavailable)?;
+    hash_password_with_salt(password, &salt, params)
+}
+
+/// 使用指定盐计算口令哈希，`params.salt_len` 被忽略
+///
+/// 迭代次数超过 [`MAX_ITERATIONS`]、输出长度超过 [`MAX_OUTPUT_LEN`] 时返回错误，保证结果可被校验
+pub fn hash_password_with_salt(password: &[u8], salt: &[u8], params: &Params) -> Result<String, Pbkdf2Error> {
+    if params.iterations > MAX_ITERATIONS {
+        return Err(Pbkdf2Error::TooManyIterations);
+    }
+    if params.output_len > MAX_OUTPUT_LEN {
+        return Err(Pbkdf2Error::InvalidOutputLength);
+    }
+    let mut hash = vec![0u8; params.output_len];
+    pbkdf2_hmac_sm3(password, salt, params.iterations, &mut hash)?;
+    Ok(format!(
+        "${}$i={}${}${}",
+        ALGORITHM_ID,
+        params.iterations,
+        base64_encode(salt, false),
+        base64_encode(&hash, false)
+    ))
+}
+
+/// 校验口令，口令不匹配返回 `Ok(false)`，字符串格式错误返回 `Err`
+///
+/// 迭代次数超过 [`MAX_ITERATIONS`] 时返回 [`Pbkdf2Error::TooManyIterations`]
+pub fn verify_password(password: &[u8], phc: &str) -> Result<bool, Pbkdf2Error> {
+    verify_password_with_limit(password, phc, MAX_ITERATIONS)
+}
+
+/// 同 [`verify_password`]，迭代次数上限由调用者指定
+pub fn verify_password_with_limit(password: &[u8], phc: &str, max_iterations: u32) -> Result<bool, Pbkdf2Error> {
+    let (iterations, salt, expected) = parse_phc(phc)?;
+    if iterations > max_iterations {
+        return Err(Pbkdf2Error::TooManyIterations);
+    }
+    let mut hash = vec![0u8; expected.len()];
+    pbkdf2_hmac_sm3(password, &salt, iterations, &mut hash)?;
+    Ok(ct_eq(&hash, &expected))
+}
+
+// 解析为 (迭代次数, 盐, 哈希)
+fn parse_phc(phc: &str) -> Result<(u32, Vec<u8>, Vec<u8>), Pbkdf2Error> {
+    let parts: Vec<&str> = phc.split('$').collect();
+    if parts.len() != 5 || !parts[0].is_empty() {
+        return Err(Pbkdf2Error::InvalidFormat);
+    }
+    if parts[1] != ALGORITHM_ID {
+        return Err(Pbkdf2Error::UnsupportedAlgorithm);
+    }
+
+    let iterations = parts[2]
+        .strip_prefix("i=")
+        .and_then(|v| v.parse::<u32>().ok())
+        .ok_or(Pbkdf2Error::InvalidFormat)?;
+    let salt = base64_decode(parts[3]).ok_or(Pbkdf2Error::InvalidFormat)?;
+    let hash = base64_decode(parts[4]).ok_or(Pbkdf2Error::InvalidFormat)?;
+    if hash.is_empty() || hash.len() > MAX_OUTPUT_LEN {
+        return Err(Pbkdf2Error::InvalidFormat);
+    }
+    Ok((iterations, salt, hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derive_hex(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> String {
+        let mut out = vec![0u8; len];
+        pbkdf2_hmac_sm3(password, salt, iterations, &mut out).unwrap();
+        out.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    // 以下期望值均由 OpenSSL 3 `kdf -kdfopt digest:SM3 PBKDF2` 计算得到
+    #[test]
+    fn test_pbkdf2_vectors() {
+        assert_eq!(
+            derive_hex(b"password", b"salt", 1, 32),
+            "4612F922A1FDCEFAF4312FC6F8F3322B489CBF24F2EA361B44C2BD8FA2C6DCB0"
+        );
+        assert_eq!(
+            derive_hex(b"password", b"salt", 2, 32),
+            "FEE723A2BC966E11DFFB66133F4E8DF577383C78ADE30E3298EDBD3E54ED85B7"
+        );
+        assert_eq!(
+            derive_hex(b"password", b"salt", 4096, 32),
+            "B6E8F2074C87432B78F62E5CED980FDFF89E86AF2F693DAB1638E2B3683045DD"
+        );
+        // 输出跨越两个PRF分组
+        assert_eq!(
+            derive_hex(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 40),
+            "3B6282AC8519F059E465ABFF0EA37B0DBFE6C672A76E6B805312D53900DB630732CCC1A88FA5512A"
+        );
+        // 截断输出是完整输出的前缀
+        assert_eq!(derive_hex(b"password", b"salt", 1, 7), "4612F922A1FDCE");
+    }
+
+    #[test]
+    fn test_pbkdf2_invalid_params() {
+        let mut out = [0u8; 32];
+        assert_eq!(pbkdf2_hmac_sm3(b"p", b"s", 0, &mut out), Err(Pbkdf2Error::InvalidIterations));
+        assert_eq!(pbkdf2_hmac_sm3(b"p", b"s", 1, &mut []), Err(Pbkdf2Error::InvalidOutputLength));
+    }
+
+    #[test]
+    fn test_phc_roundtrip() {
+        let params = Params { iterations: 1000, output_len: 24, salt_len: 12 };
+        let phc = hash_password(b"correct horse", &params).unwrap();
+        assert!(phc.starts_with("$pbkdf2-sm3$i=1000$"));
+        assert!(verify_password(b"correct horse", &phc).unwrap());
+        assert!(!verify_password(b"battery staple", &phc).unwrap());
+
+        // 随机盐每次不同
+        assert_ne!(hash_password(b"correct horse", &params).unwrap(), phc);
+    }
+
+    #[test]
+    fn test_phc_with_salt() {
+        let params = Params { iterations: 4096, ..Params::default() };
+        let phc = hash_password_with_salt(b"password", b"salt", &params).unwrap();
+        assert_eq!(phc, "$pbkdf2-sm3$i=4096$c2FsdA$tujyB0yHQyt49i5c7ZgP3/iehq8vaT2rFjjis2gwRd0");
+        assert!(verify_password(b"password", &phc).unwrap());
+    }
+
+    #[test]
+    fn test_phc_malformed() {
+        assert_eq!(verify_password(b"p", "pbkdf2-sm3$i=1$c2FsdA$AAAA"), Err(Pbkdf2Error::InvalidFormat));
+        assert_eq!(verify_password(b"p", "$pbkdf2-sha256$i=1$c2FsdA$AAAA"), Err(Pbkdf2Error::UnsupportedAlgorithm));
+        assert_eq!(verify_password(b"p", "$pbkdf2-sm3$n=1$c2FsdA$AAAA"), Err(Pbkdf2Error::InvalidFormat));
+        assert_eq!(verify_password(b"p", "$pbkdf2-sm3$i=1$c2FsdA$"), Err(Pbkdf2Error::InvalidFormat));
+        assert_eq!(verify_password(b"p", "$pbkdf2-sm3$i=0$c2FsdA$AAAA"), Err(Pbkdf2Error::InvalidIterations));
+        let long_hash = format!("$pbkdf2-sm3$i=1$c2FsdA${}", "A".repeat(88));
+        assert_eq!(verify_password(b"p", &long_hash), Err(Pbkdf2Error::InvalidFormat));
+    }
+
+    #[test]
+    fn test_iteration_limit() {
+        // 超出上限时在派生之前返回，不会长时间计算
+        assert_eq!(verify_password(b"p", "$pbkdf2-sm3$i=4294967295$c2FsdA$AAAA"), Err(Pbkdf2Error::TooManyIterations));
+        let phc = hash_password_with_salt(b"password", b"salt", &Params { iterations: 4096, ..Params::default() }).unwrap();
+        assert_eq!(verify_password_with_limit(b"password", &phc, 1000), Err(Pbkdf2Error::TooManyIterations));
+        assert!(verify_password_with_limit(b"password", &phc, 4096).unwrap());
+
+        let params = Params { iterations: MAX_ITERATIONS + 1, ..Params::default() };
+        assert_eq!(hash_password(b"p", &params), Err(Pbkdf2Error::TooManyIterations));
+        let params = Params { output_len: MAX_OUTPUT_LEN + 1, ..Params::default() };
+        assert_eq!(hash_password(b"p", &params), Err(Pbkdf2Error::InvalidOutputLength));
+    }
+}
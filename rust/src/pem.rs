@@ -0,0 +1,78 @@
+// crate 内部使用的 PEM（RFC 7468）编解码
+
+use crate::util::{base64_decode, base64_encode};
+
+/// 编码为 PEM，每行64个字符，以 `\n` 换行
+pub(crate) fn encode(label: &str, der: &[u8]) -> String {
+    let b64 = base64_encode(der, true);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in b64.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).unwrap());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// 解码第一个标签为 `label` 的 PEM 块，块外的文本被忽略
+pub(crate) fn decode(text: &str, label: &str) -> Option<Vec<u8>> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let start = text.find(&begin)? + begin.len();
+    let stop = start + text[start..].find(&end)?;
+    // 允许 CRLF 与行首尾空白，不支持 RFC 1421 头部
+    let body: String = text[start..stop].split_whitespace().collect();
+    if body.contains(':') {
+        return None;
+    }
+    base64_decode(&body)
+}
+
+/// 依次解码所有标签为 `label` 的 PEM 块，任一块损坏时返回 `None`
+pub(crate) fn decode_all(text: &str, label: &str) -> Option<Vec<Vec<u8>>> {
+    let end = format!("-----END {}-----", label);
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while rest.contains(&format!("-----BEGIN {}-----", label)) {
+        blocks.push(decode(rest, label)?);
+        let stop = rest.find(&end)? + end.len();
+        rest = &rest[stop..];
+    }
+    Some(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let pem = encode("TEST DATA", &data);
+        let lines: Vec<&str> = pem.lines().collect();
+        assert_eq!(lines[0], "-----BEGIN TEST DATA-----");
+        assert_eq!(lines[1].len(), 64);
+        assert_eq!(lines.last().unwrap(), &"-----END TEST DATA-----");
+        assert_eq!(decode(&pem, "TEST DATA").unwrap(), data);
+
+        // CRLF 与块外文本
+        let crlf = format!("comment\r\n{}trailer", pem.replace('\n', "\r\n"));
+        assert_eq!(decode(&crlf, "TEST DATA").unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_all() {
+        let text = format!("{}{}\n{}", encode("A", b"1"), encode("B", b"2"), encode("A", b"3"));
+        assert_eq!(decode_all(&text, "A").unwrap(), [b"1".to_vec(), b"3".to_vec()]);
+        assert_eq!(decode_all(&text, "C").unwrap(), Vec::<Vec<u8>>::new());
+        assert!(decode_all(&format!("{}-----BEGIN A-----\n", text), "A").is_none());
+    }
+
+    #[test]
+    fn test_wrong_label_or_garbage() {
+        let pem = encode("A", b"abc");
+        assert!(decode(&pem, "B").is_none());
+        assert!(decode("-----BEGIN A-----\n!!!!\n-----END A-----\n", "A").is_none());
+        assert!(decode("-----BEGIN A-----\nYWJj\n", "A").is_none());
+    }
+}
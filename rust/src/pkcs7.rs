@@ -0,0 +1,177 @@
+//! SM2 密码消息语法（GM/T 0010）
+//!
+//! 生成时使用 GM/T 0010 的内容类型 OID（1.2.156.10197.6.1.4.2 分支），解析时也接受
+//! PKCS#7 的对应 OID（1.2.840.113549.1.7），以便与 OpenSSL 互通。签名使用默认ID，
+//! 数字信封以 SM4-CBC 加密内容、以 SM2 加密内容加密密钥。
+
+use std::error::Error;
+use std::fmt;
+
+use crate::der::{self, DerError, Reader};
+use crate::sm2::SM2Error;
+use crate::x509::X509Error;
+
+mod enveloped;
+mod signed;
+
+pub use enveloped::{EnvelopedData, RecipientInfo};
+pub use signed::{SignedData, SignedDataBuilder, SignerInfo};
+
+const PKCS7_LABEL: &str = "PKCS7";
+
+/// 内容类型与属性的 OID
+mod oid {
+    /// data 1.2.156.10197.6.1.4.2.1
+    pub(crate) const DATA: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x06, 0x01, 0x04, 0x02, 0x01];
+    /// signedData 1.2.156.10197.6.1.4.2.2
+    pub(crate) const SIGNED_DATA: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x06, 0x01, 0x04, 0x02, 0x02];
+    /// envelopedData 1.2.156.10197.6.1.4.2.3
+    pub(crate) const ENVELOPED_DATA: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x06, 0x01, 0x04, 0x02, 0x03];
+    /// PKCS#7 data 1.2.840.113549.1.7.1
+    pub(crate) const PKCS7_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x01];
+    /// PKCS#7 signedData 1.2.840.113549.1.7.2
+    pub(crate) const PKCS7_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+    /// PKCS#7 envelopedData 1.2.840.113549.1.7.3
+    pub(crate) const PKCS7_ENVELOPED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x03];
+    /// contentType 1.2.840.113549.1.9.3
+    pub(crate) const CONTENT_TYPE: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x03];
+    /// messageDigest 1.2.840.113549.1.9.4
+    pub(crate) const MESSAGE_DIGEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x04];
+    /// signingTime 1.2.840.113549.1.9.5
+    pub(crate) const SIGNING_TIME: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x05];
+}
+
+/// 消息语法相关错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pkcs7Error {
+    InvalidEncoding,
+    UnsupportedAlgorithm,
+    UnsupportedContentType,
+    /// 分离式签名验证时未提供原文
+    MissingContent,
+    DigestMismatch,
+    InvalidSignature,
+    SignerNotFound,
+    KeyMismatch,
+    /// 信封中没有与证书对应的接收者
+    RecipientNotFound,
+    /// 内容加密密钥或内容解密失败
+    DecryptionFailed,
+    /// 系统随机数生成器不可用
+    RandomUnavailable,
+    /// 证书解析或证书路径验证失败
+    Certificate(X509Error),
+}
+
+impl fmt::Display for Pkcs7Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pkcs7Error::InvalidEncoding => write!(f, "Malformed message encoding"),
+            Pkcs7Error::UnsupportedAlgorithm => write!(f, "Unsupported algorithm"),
+            Pkcs7Error::UnsupportedContentType => write!(f, "Unsupported content type"),
+            Pkcs7Error::MissingContent => write!(f, "Content is required to verify a detached signature"),
+            Pkcs7Error::DigestMismatch => write!(f, "Message digest does not match the content"),
+            Pkcs7Error::InvalidSignature => write!(f, "Signature verification failed"),
+            Pkcs7Error::SignerNotFound => write!(f, "No certificate found for signer"),
+            Pkcs7Error::KeyMismatch => write!(f, "Signing key does not match the certificate"),
+            Pkcs7Error::RecipientNotFound => write!(f, "No recipient info found for certificate"),
+            Pkcs7Error::DecryptionFailed => write!(f, "Failed to decrypt enveloped content"),
+            Pkcs7Error::RandomUnavailable => write!(f, "Secure random number generator unavailable"),
+            Pkcs7Error::Certificate(e) => write!(f, "Certificate error: {}", e),
+        }
+    }
+}
+
+impl Error for Pkcs7Error {}
+
+impl From<DerError> for Pkcs7Error {
+    fn from(_: DerError) -> Self {
+        Pkcs7Error::InvalidEncoding
+    }
+}
+
+impl From<X509Error> for Pkcs7Error {
+    fn from(e: X509Error) -> Self {
+        Pkcs7Error::Certificate(e)
+    }
+}
+
+impl From<SM2Error> for Pkcs7Error {
+    fn from(e: SM2Error) -> Self {
+        match e {
+            SM2Error::UnsupportedAlgorithm => Pkcs7Error::UnsupportedAlgorithm,
+            SM2Error::RandomUnavailable => Pkcs7Error::RandomUnavailable,
+            _ => Pkcs7Error::InvalidEncoding,
+        }
+    }
+}
+
+/// 两个分支中的 data 类型
+fn is_data(content_type: &[u8]) -> bool {
+    content_type == oid::DATA || content_type == oid::PKCS7_DATA
+}
+
+/// 解析 `ContentInfo`，返回 (内容类型, [0] 中的内容)
+fn parse_content_info(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), DerError> {
+    let mut outer = Reader::new(bytes);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let content_type = seq.oid()?;
+    let content = seq.read_optional(der::context(0))?;
+    seq.finish()?;
+    Ok((content_type, content))
+}
+
+/// 编码 `ContentInfo`，`content` 为 [0] 中的完整元素
+fn content_info(content_type: &[u8], content: Option<&[u8]>) -> Vec<u8> {
+    let content = content.map_or(Vec::new(), |c| der::constructed(der::context(0), &[c]));
+    der::sequence(&[&der::oid(content_type), &content])
+}
+
+/// SM3 的 AlgorithmIdentifier，参数可省略或为 NULL
+fn parse_digest_algorithm(reader: &mut Reader) -> Result<(), Pkcs7Error> {
+    let mut alg = reader.sequence()?;
+    if alg.oid()? != der::oid::SM3 {
+        return Err(Pkcs7Error::UnsupportedAlgorithm);
+    }
+    alg.optional_null()?;
+    alg.finish()?;
+    Ok(())
+}
+
+fn digest_algorithm() -> Vec<u8> {
+    der::sequence(&[&der::oid(der::oid::SM3)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oids() {
+        let cases = [
+            (oid::DATA, "1.2.156.10197.6.1.4.2.1"),
+            (oid::SIGNED_DATA, "1.2.156.10197.6.1.4.2.2"),
+            (oid::PKCS7_DATA, "1.2.840.113549.1.7.1"),
+            (oid::ENVELOPED_DATA, "1.2.156.10197.6.1.4.2.3"),
+            (oid::PKCS7_SIGNED_DATA, "1.2.840.113549.1.7.2"),
+            (oid::PKCS7_ENVELOPED_DATA, "1.2.840.113549.1.7.3"),
+            (oid::CONTENT_TYPE, "1.2.840.113549.1.9.3"),
+            (oid::MESSAGE_DIGEST, "1.2.840.113549.1.9.4"),
+            (oid::SIGNING_TIME, "1.2.840.113549.1.9.5"),
+        ];
+        for (bytes, dotted) in cases {
+            assert_eq!(der::oid_to_string(bytes).unwrap(), dotted);
+        }
+    }
+
+    #[test]
+    fn test_content_info() {
+        let encoded = content_info(oid::DATA, Some(&der::octet_string(b"abc")));
+        let (content_type, content) = parse_content_info(&encoded).unwrap();
+        assert!(is_data(content_type));
+        assert_eq!(content.unwrap(), der::octet_string(b"abc"));
+        assert_eq!(parse_content_info(&content_info(oid::PKCS7_DATA, None)).unwrap().1, None);
+        assert!(parse_content_info(&der::sequence(&[&der::octet_string(b"x")])).is_err());
+    }
+}
@@ -0,0 +1,530 @@
+//! 签名数据类型 SignedData（GM/T 0010 第8章）
+//!
+//! 摘要算法为 SM3；签名算法写为 SM2-with-SM3，与 GmSSL、OpenSSL 一致，解析时也接受 GM/T 0010 中的 sm2-1。
+//! 签名者以颁发者与序列号标识，签名者证书须包含在消息中。
+
+use std::time::SystemTime;
+
+use crate::der::{self, Reader};
+use crate::pem;
+use crate::sm2::{DEFAULT_DISTID, Signature, SigningKey};
+use crate::sm3::Sm3;
+use crate::x509::{Certificate, CertificateRevocationList, Name, TrustStore, time};
+
+use super::{PKCS7_LABEL, Pkcs7Error, oid};
+
+/// 签名者信息
+#[derive(Clone, Debug)]
+pub struct SignerInfo {
+    issuer: Name,
+    serial: Vec<u8>,
+    /// 以 SET 标签编码的签名属性，即签名的原文
+    signed_attributes: Option<Vec<u8>>,
+    content_type: Option<Vec<u8>>,
+    message_digest: Option<Vec<u8>>,
+    signing_time: Option<SystemTime>,
+    signature: Signature,
+}
+
+impl SignerInfo {
+    fn parse(raw: &[u8]) -> Result<Self, Pkcs7Error> {
+        let mut outer = Reader::new(raw);
+        let mut seq = outer.sequence()?;
+        outer.finish()?;
+        if seq.small_uint()? != 1 {
+            return Err(Pkcs7Error::InvalidEncoding);
+        }
+        let mut id = seq.sequence()?;
+        let issuer = Name::parse(id.read_raw(der::SEQUENCE)?)?;
+        let serial = id.uint()?.to_vec();
+        id.finish()?;
+        super::parse_digest_algorithm(&mut seq)?;
+
+        let mut signed_attributes = None;
+        let (mut content_type, mut message_digest, mut signing_time) = (None, None, None);
+        if seq.peek_tag() == Some(der::context(0)) {
+            // [0] IMPLICIT，签名时按 SET OF 编码
+            let mut attributes = seq.read_raw(der::context(0))?.to_vec();
+            attributes[0] = der::SET;
+            (content_type, message_digest, signing_time) = parse_attributes(&attributes)?;
+            signed_attributes = Some(attributes);
+        }
+
+        let mut alg = seq.sequence()?;
+        let alg_oid = alg.oid()?;
+        if alg_oid != der::oid::SM2_WITH_SM3 && alg_oid != der::oid::SM2_SIGN {
+            return Err(Pkcs7Error::UnsupportedAlgorithm);
+        }
+        alg.optional_null()?;
+        alg.finish()?;
+        let signature = Signature::from_der(seq.octet_string()?).map_err(|_| Pkcs7Error::InvalidSignature)?;
+        // 非签名属性被忽略
+        seq.read_optional(der::context(1))?;
+        seq.finish()?;
+        Ok(SignerInfo { issuer, serial, signed_attributes, content_type, message_digest, signing_time, signature })
+    }
+
+    /// 签名者证书的颁发者
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    /// 签名者证书的序列号
+    pub fn serial_number(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn has_signed_attributes(&self) -> bool {
+        self.signed_attributes.is_some()
+    }
+
+    /// 签名属性中的签名时间，未经验证
+    pub fn signing_time(&self) -> Option<SystemTime> {
+        self.signing_time
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn is_issued(&self, cert: &Certificate) -> bool {
+        cert.issuer() == &self.issuer && cert.serial_number() == self.serial.as_slice()
+    }
+}
+
+/// 已解析的签名数据
+#[derive(Clone, Debug)]
+pub struct SignedData {
+    der: Vec<u8>,
+    content_type: Vec<u8>,
+    content: Option<Vec<u8>>,
+    certificates: Vec<Certificate>,
+    crls: Vec<CertificateRevocationList>,
+    signer_infos: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    /// 解析 `ContentInfo` 包装的签名数据，被签名内容必须为 data 类型
+    pub fn from_der(bytes: &[u8]) -> Result<Self, Pkcs7Error> {
+        let (outer_type, content) = super::parse_content_info(bytes)?;
+        if outer_type != oid::SIGNED_DATA && outer_type != oid::PKCS7_SIGNED_DATA {
+            return Err(Pkcs7Error::UnsupportedContentType);
+        }
+        let mut outer = Reader::new(content.ok_or(Pkcs7Error::InvalidEncoding)?);
+        let mut seq = outer.sequence()?;
+        outer.finish()?;
+        if seq.small_uint()? != 1 {
+            return Err(Pkcs7Error::InvalidEncoding);
+        }
+        let mut digest_algorithms = Reader::new(seq.read(der::SET)?);
+        while !digest_algorithms.is_empty() {
+            super::parse_digest_algorithm(&mut digest_algorithms)?;
+        }
+
+        let (content_type, content) = super::parse_content_info(seq.read_raw(der::SEQUENCE)?)?;
+        if !super::is_data(content_type) {
+            return Err(Pkcs7Error::UnsupportedContentType);
+        }
+        let content = match content {
+            Some(c) => {
+                let mut r = Reader::new(c);
+                let data = r.octet_string()?;
+                r.finish()?;
+                Some(data.to_vec())
+            }
+            None => None,
+        };
+
+        let mut certificates = Vec::new();
+        if let Some(certs) = seq.read_optional(der::context(0))? {
+            let mut certs = Reader::new(certs);
+            while !certs.is_empty() {
+                certificates.push(Certificate::from_der(certs.read_raw(der::SEQUENCE)?)?);
+            }
+        }
+        let mut crls = Vec::new();
+        if let Some(list) = seq.read_optional(der::context(1))? {
+            let mut list = Reader::new(list);
+            while !list.is_empty() {
+                crls.push(CertificateRevocationList::from_der(list.read_raw(der::SEQUENCE)?)?);
+            }
+        }
+        let mut signers = Reader::new(seq.read(der::SET)?);
+        seq.finish()?;
+        let mut signer_infos = Vec::new();
+        while !signers.is_empty() {
+            signer_infos.push(SignerInfo::parse(signers.read_raw(der::SEQUENCE)?)?);
+        }
+
+        Ok(SignedData { der: bytes.to_vec(), content_type: content_type.to_vec(), content, certificates, crls, signer_infos })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, Pkcs7Error> {
+        let bytes = pem::decode(text, PKCS7_LABEL).ok_or(Pkcs7Error::InvalidEncoding)?;
+        Self::from_der(&bytes)
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode(PKCS7_LABEL, &self.der)
+    }
+
+    /// 附带的原文，分离式签名为 `None`
+    pub fn content(&self) -> Option<&[u8]> {
+        self.content.as_deref()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.content.is_none()
+    }
+
+    /// 消息中的全部证书
+    pub fn certificates(&self) -> &[Certificate] {
+        &self.certificates
+    }
+
+    pub fn crls(&self) -> &[CertificateRevocationList] {
+        &self.crls
+    }
+
+    pub fn signer_infos(&self) -> &[SignerInfo] {
+        &self.signer_infos
+    }
+
+    /// 验证全部签名，返回与 [`signer_infos`](Self::signer_infos) 顺序一致的签名者证书
+    ///
+    /// 分离式签名须由 `detached_content` 提供原文，附带原文时忽略该参数。不检查证书是否可信，
+    /// 见 [`verify_trusted`](Self::verify_trusted)。
+    pub fn verify(&self, detached_content: Option<&[u8]>) -> Result<Vec<&Certificate>, Pkcs7Error> {
+        let content = self.content.as_deref().or(detached_content).ok_or(Pkcs7Error::MissingContent)?;
+        if self.signer_infos.is_empty() {
+            return Err(Pkcs7Error::SignerNotFound);
+        }
+        let digest = Sm3::new().update(content).finalize();
+        let mut signers = Vec::new();
+        for info in &self.signer_infos {
+            let cert = self.certificates.iter().find(|c| info.is_issued(c)).ok_or(Pkcs7Error::SignerNotFound)?;
+            let message = match &info.signed_attributes {
+                Some(attributes) => {
+                    if info.content_type.as_deref() != Some(self.content_type.as_slice()) {
+                        return Err(Pkcs7Error::InvalidEncoding);
+                    }
+                    if info.message_digest.as_deref() != Some(&digest[..]) {
+                        return Err(Pkcs7Error::DigestMismatch);
+                    }
+                    attributes.as_slice()
+                }
+                None => content,
+            };
+            if !cert.public_key().verify(message, &info.signature) {
+                return Err(Pkcs7Error::InvalidSignature);
+            }
+            signers.push(cert);
+        }
+        Ok(signers)
+    }
+
+    /// 验证签名，并以消息中的其他证书为中间证书，在 `time` 时刻验证签名者证书的路径
+    pub fn verify_trusted(&self, detached_content: Option<&[u8]>, store: &TrustStore, time: SystemTime) -> Result<Vec<&Certificate>, Pkcs7Error> {
+        let signers = self.verify(detached_content)?;
+        for cert in &signers {
+            store.verify(cert, &self.certificates, time)?;
+        }
+        Ok(signers)
+    }
+}
+
+/// 签名数据构造器
+///
+/// 默认附带原文并添加签名属性（内容类型、消息摘要、签名时间）。签名者证书自动加入消息。
+#[derive(Clone, Debug)]
+pub struct SignedDataBuilder<'a> {
+    content: &'a [u8],
+    detached: bool,
+    signed_attributes: bool,
+    signing_time: Option<SystemTime>,
+    certificates: Vec<Certificate>,
+    signers: Vec<(Certificate, &'a SigningKey)>,
+}
+
+impl<'a> SignedDataBuilder<'a> {
+    pub fn new(content: &'a [u8]) -> Self {
+        SignedDataBuilder { content, detached: false, signed_attributes: true, signing_time: None, certificates: Vec::new(), signers: Vec::new() }
+    }
+
+    /// 分离式签名，消息中不包含原文
+    pub fn detached(mut self, detached: bool) -> Self {
+        self.detached = detached;
+        self
+    }
+
+    /// 是否添加签名属性；不添加时直接对原文签名
+    pub fn signed_attributes(mut self, enabled: bool) -> Self {
+        self.signed_attributes = enabled;
+        self
+    }
+
+    /// 签名时间，未设置时取当前时间
+    pub fn signing_time(mut self, time: SystemTime) -> Self {
+        self.signing_time = Some(time);
+        self
+    }
+
+    /// 附加证书，例如签名者的中间 CA 证书
+    pub fn add_certificate(mut self, cert: &Certificate) -> Self {
+        if !self.certificates.contains(cert) {
+            self.certificates.push(cert.clone());
+        }
+        self
+    }
+
+    /// 添加签名者，`key` 必须与证书公钥对应
+    pub fn add_signer(mut self, cert: &Certificate, key: &'a SigningKey) -> Result<Self, Pkcs7Error> {
+        if key.verifying_key().to_sec1_bytes() != cert.public_key().to_sec1_bytes() {
+            return Err(Pkcs7Error::KeyMismatch);
+        }
+        self = self.add_certificate(cert);
+        self.signers.push((cert.clone(), key));
+        Ok(self)
+    }
+
+    pub fn build(self) -> Result<SignedData, Pkcs7Error> {
+        let digest = Sm3::new().update(self.content).finalize();
+        let signing_time = self.signing_time.unwrap_or_else(SystemTime::now);
+        let mut signer_infos = Vec::new();
+        for (cert, key) in &self.signers {
+            let (attributes, signature) = if self.signed_attributes {
+                let mut attributes = [
+                    attribute(oid::CONTENT_TYPE, &der::oid(oid::DATA)),
+                    attribute(oid::SIGNING_TIME, &time::encode(signing_time)),
+                    attribute(oid::MESSAGE_DIGEST, &der::octet_string(&digest)),
+                ];
+                // DER 中 SET OF 按编码排序
+                attributes.sort();
+                let parts: Vec<&[u8]> = attributes.iter().map(Vec::as_slice).collect();
+                let signature = sign(key, &der::constructed(der::SET, &parts))?;
+                (der::constructed(der::context(0), &parts), signature)
+            } else {
+                (Vec::new(), sign(key, self.content)?)
+            };
+            signer_infos.push(der::sequence(&[
+                &der::small_uint(1),
+                &der::sequence(&[cert.issuer().as_der(), &der::uint(cert.serial_number())]),
+                &super::digest_algorithm(),
+                &attributes,
+                &der::sequence(&[&der::oid(der::oid::SM2_WITH_SM3)]),
+                &der::octet_string(&signature.to_der()),
+            ]));
+        }
+
+        let digest_algorithms = if self.signers.is_empty() { Vec::new() } else { super::digest_algorithm() };
+        let content = (!self.detached).then(|| der::octet_string(self.content));
+        let certificates: Vec<&[u8]> = self.certificates.iter().map(Certificate::as_der).collect();
+        let certificates = if certificates.is_empty() { Vec::new() } else { der::constructed(der::context(0), &certificates) };
+        let signed_data = der::sequence(&[
+            &der::small_uint(1),
+            &der::constructed(der::SET, &[&digest_algorithms]),
+            &super::content_info(oid::DATA, content.as_deref()),
+            &certificates,
+            &der::constructed(der::SET, &signer_infos.iter().map(Vec::as_slice).collect::<Vec<_>>()),
+        ]);
+        SignedData::from_der(&super::content_info(oid::SIGNED_DATA, Some(&signed_data)))
+    }
+}
+
+/// 签名属性中的 (内容类型, 消息摘要, 签名时间)
+type Attributes = (Option<Vec<u8>>, Option<Vec<u8>>, Option<SystemTime>);
+
+/// 前两者必须存在，每种属性只能出现一次，其他属性被忽略
+fn parse_attributes(raw: &[u8]) -> Result<Attributes, Pkcs7Error> {
+    let mut outer = Reader::new(raw);
+    let mut set = Reader::new(outer.read(der::SET)?);
+    outer.finish()?;
+    let (mut content_type, mut message_digest, mut signing_time) = (None, None, None);
+    let mut seen = Vec::new();
+    while !set.is_empty() {
+        let mut attribute = set.sequence()?;
+        let id = attribute.oid()?;
+        let mut values = Reader::new(attribute.read(der::SET)?);
+        attribute.finish()?;
+        if seen.contains(&id) {
+            return Err(Pkcs7Error::InvalidEncoding);
+        }
+        seen.push(id);
+        match id {
+            oid::CONTENT_TYPE => content_type = Some(values.oid()?.to_vec()),
+            oid::MESSAGE_DIGEST => message_digest = Some(values.octet_string()?.to_vec()),
+            oid::SIGNING_TIME => signing_time = Some(values.read_any().and_then(|(tag, t)| time::parse(tag, t))?),
+            _ => continue,
+        }
+        values.finish()?;
+    }
+    if content_type.is_none() || message_digest.is_none() {
+        return Err(Pkcs7Error::InvalidEncoding);
+    }
+    Ok((content_type, message_digest, signing_time))
+}
+
+fn attribute(id: &[u8], value: &[u8]) -> Vec<u8> {
+    der::sequence(&[&der::oid(id), &der::constructed(der::SET, &[value])])
+}
+
+/// 证书公钥总是使用默认ID
+fn sign(key: &SigningKey, message: &[u8]) -> Result<Signature, Pkcs7Error> {
+    let signature = if key.distid() == DEFAULT_DISTID {
+        key.try_sign(message)?
+    } else {
+        key.clone().with_distid(DEFAULT_DISTID).unwrap().try_sign(message)?
+    };
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x509::tests::ROOT;
+    use crate::x509::{BasicConstraints, CertificateBuilder, KeyUsage, X509Error, attr};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // openssl cms -sign -md sm3 -nodetach -nosmimecap -keyopt distid:1234567812345678 -certfile INTERMEDIATE
+    // 由 LEAF 对 "hello gm" 签名，使用 PKCS#7 的 OID
+    const OPENSSL_SIGNED: &str = "-----BEGIN PKCS7-----
+MIIFeQYJKoZIhvcNAQcCoIIFajCCBWYCAQExDjAMBggqgRzPVQGDEQUAMBcGCSqG
+SIb3DQEHAaAKBAhoZWxsbyBnbaCCBA0wggHOMIIBdaADAgECAgECMAoGCCqBHM9V
+AYN1MDkxCzAJBgNVBAYTAkNOMRAwDgYDVQQKDAdHTSBUZXN0MRgwFgYDVQQDDA9H
+TSBUZXN0IFJvb3QgQ0EwHhcNMjQwMTAxMDAwMDAwWhcNMzQwMTAxMDAwMDAwWjBB
+MQswCQYDVQQGEwJDTjEQMA4GA1UECgwHR00gVGVzdDEgMB4GA1UEAwwXR00gVGVz
+dCBJbnRlcm1lZGlhdGUgQ0EwWTATBgcqhkjOPQIBBggqgRzPVQGCLQNCAARUNRvA
+D7AfEpLA25JLrFJl0Hf7cUCfmcHa94rkzIwJXqOjHwo0g4D8kLDEFNHuzf3aKxoG
+iqLgmw/Ew4oo1LPro2YwZDASBgNVHRMBAf8ECDAGAQH/AgEAMA4GA1UdDwEB/wQE
+AwIBBjAdBgNVHQ4EFgQUgcOP0hVzrsgPllTDMrgWLjeEs7owHwYDVR0jBBgwFoAU
+u9q3t0XU7wYndcLNEurShwbvtSUwCgYIKoEcz1UBg3UDRwAwRAIgD6ynOtFksslu
+DXCiyPRLmbEgmHyK7fYBi2y2521X9N8CIAh62FYEvUmu0EfqzDz+GvhF3qe//YoD
+onPz+qWV1THdMIICNzCCAd6gAwIBAgIIASNFZ4mrze8wCgYIKoEcz1UBg3UwQTEL
+MAkGA1UEBhMCQ04xEDAOBgNVBAoMB0dNIFRlc3QxIDAeBgNVBAMMF0dNIFRlc3Qg
+SW50ZXJtZWRpYXRlIENBMB4XDTI1MDEwMTAwMDAwMFoXDTI2MDEwMTAwMDAwMFow
+RjELMAkGA1UEBhMCQ04xEDAOBgNVBAgMB0JlaWppbmcxEDAOBgNVBAoMB0dNIFRl
+c3QxEzARBgNVBAMMCmV4YW1wbGUuY24wWTATBgcqhkjOPQIBBggqgRzPVQGCLQNC
+AARz7hpEvQ1CvPYNdaH832ymSIsr5SbTJ2u8d+eWmRhVH77W+0p70F1UgC1E8llw
+F8MTPS2VeR3D16TIqXDQtjvpo4G6MIG3MAkGA1UdEwQCMAAwDgYDVR0PAQH/BAQD
+AgWgMB0GA1UdJQQWMBQGCCsGAQUFBwMBBggrBgEFBQcDAjA7BgNVHREENDAyggpl
+eGFtcGxlLmNuggwqLmV4YW1wbGUuY26HBMCoAQGBEGFkbWluQGV4YW1wbGUuY24w
+HQYDVR0OBBYEFHcf3HjkREdDA1rOL334ZsFN95WbMB8GA1UdIwQYMBaAFIHDj9IV
+c67ID5ZUwzK4Fi43hLO6MAoGCCqBHM9VAYN1A0cAMEQCICiOrRkTdyF0oLs2rLVs
+1g6rrcc1RbU9ydsOq41U8ycHAiAcliqeQdN87rVhhcgoNLRM+nJRg/HA3VinbvVN
+O0nUSTGCASUwggEhAgEBME0wQTELMAkGA1UEBhMCQ04xEDAOBgNVBAoMB0dNIFRl
+c3QxIDAeBgNVBAMMF0dNIFRlc3QgSW50ZXJtZWRpYXRlIENBAggBI0VniavN7zAM
+BggqgRzPVQGDEQUAoGkwGAYJKoZIhvcNAQkDMQsGCSqGSIb3DQEHATAcBgkqhkiG
+9w0BCQUxDxcNMjYxMDE4MTgwNDMyWjAvBgkqhkiG9w0BCQQxIgQg0k4TEF/YXcpK
+119HZuHGbqqudmx4mICCr7TWc8KTV4AwCgYIKoEcz1UBg3UESDBGAiEA4PfYM8+2
+xPPR8GlR461JtTQ5UTgays05x5fQMMm83lgCIQCiMJJzS6YRtXj5neLsWCTM/TCS
+xVqa1jEH7mAPwtErxA==
+-----END PKCS7-----
+";
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    /// 自签名 CA 及其签发的签名证书
+    fn pki() -> (Certificate, Certificate, SigningKey) {
+        let (ca_key, key) = (SigningKey::random(), SigningKey::random());
+        let ca = CertificateBuilder::new(Name::new(&[(attr::COMMON_NAME, "CA")]).unwrap(), ca_key.verifying_key(), at(1704067200), at(1798761600))
+            .basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .self_signed(&ca_key)
+            .unwrap();
+        let cert = CertificateBuilder::new(Name::new(&[(attr::COMMON_NAME, "signer")]).unwrap(), key.verifying_key(), at(1704067200), at(1798761600))
+            .key_usage(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::NON_REPUDIATION)
+            .sign(&ca, &ca_key)
+            .unwrap();
+        (ca, cert, key)
+    }
+
+    #[test]
+    fn test_openssl() {
+        let signed = SignedData::from_pem(OPENSSL_SIGNED).unwrap();
+        assert_eq!(signed.content(), Some(&b"hello gm"[..]));
+        assert_eq!(signed.certificates().len(), 2);
+        let info = &signed.signer_infos()[0];
+        assert_eq!(info.serial_number(), [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+        assert_eq!(info.signing_time(), Some(at(1792346672)));
+
+        let signers = signed.verify(None).unwrap();
+        assert_eq!(signers[0].subject().common_name(), Some("example.cn"));
+        let store = TrustStore::from_pem(ROOT).unwrap();
+        signed.verify_trusted(None, &store, at(1750000000)).unwrap();
+        assert_eq!(signed.verify_trusted(None, &store, at(1800000000)), Err(Pkcs7Error::Certificate(X509Error::Expired)));
+        assert_eq!(signed.verify_trusted(None, &TrustStore::new(), at(1750000000)), Err(Pkcs7Error::Certificate(X509Error::UnknownIssuer)));
+    }
+
+    #[test]
+    fn test_attached() {
+        let (ca, cert, key) = pki();
+        let signed = SignedDataBuilder::new(b"document").signing_time(at(1750000000)).add_signer(&cert, &key).unwrap().build().unwrap();
+        assert!(!signed.is_detached());
+        assert_eq!(signed.content(), Some(&b"document"[..]));
+        // 外层为 GM/T 0010 的 signedData
+        assert_eq!(&signed.as_der()[6..16], oid::SIGNED_DATA);
+        let info = &signed.signer_infos()[0];
+        assert!(info.has_signed_attributes());
+        assert_eq!(info.signing_time(), Some(at(1750000000)));
+        assert_eq!(info.issuer(), ca.subject());
+
+        let reparsed = SignedData::from_pem(&signed.to_pem()).unwrap();
+        assert_eq!(reparsed.verify(Some(b"ignored")).unwrap(), [&cert]);
+        let store = TrustStore::from_pem(&ca.to_pem()).unwrap();
+        signed.verify_trusted(None, &store, at(1750000000)).unwrap();
+    }
+
+    #[test]
+    fn test_detached() {
+        let (_, cert, key) = pki();
+        let signed = SignedDataBuilder::new(b"document").detached(true).add_signer(&cert, &key).unwrap().build().unwrap();
+        assert!(signed.is_detached());
+        assert!(signed.signer_infos()[0].signing_time().is_some());
+        assert_eq!(signed.verify(None), Err(Pkcs7Error::MissingContent));
+        signed.verify(Some(b"document")).unwrap();
+        assert_eq!(signed.verify(Some(b"documenT")), Err(Pkcs7Error::DigestMismatch));
+
+        // 无签名属性时直接对原文签名
+        let bare = SignedDataBuilder::new(b"document").detached(true).signed_attributes(false).add_signer(&cert, &key).unwrap().build().unwrap();
+        assert!(!bare.signer_infos()[0].has_signed_attributes());
+        bare.verify(Some(b"document")).unwrap();
+        assert_eq!(bare.verify(Some(b"documenT")), Err(Pkcs7Error::InvalidSignature));
+    }
+
+    #[test]
+    fn test_signers() {
+        let (ca, cert, key) = pki();
+        let (_, other, other_key) = pki();
+        assert_eq!(SignedDataBuilder::new(b"x").add_signer(&cert, &other_key).err(), Some(Pkcs7Error::KeyMismatch));
+
+        // 多个签名者，并附带 CA 证书
+        let custom = other_key.clone().with_distid(b"alice").unwrap();
+        let signed = SignedDataBuilder::new(b"contract")
+            .add_certificate(&ca)
+            .add_signer(&cert, &key)
+            .unwrap()
+            .add_signer(&other, &custom)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(signed.certificates().len(), 3);
+        assert_eq!(signed.verify(None).unwrap(), [&cert, &other]);
+
+        // 签名者信息中的序列号与证书不符
+        let mut der = signed.as_der().to_vec();
+        let serial = cert.serial_number();
+        let pos = der.windows(serial.len()).rposition(|w| w == serial).unwrap();
+        der[pos + serial.len() - 1] ^= 1;
+        assert_eq!(SignedData::from_der(&der).unwrap().verify(None), Err(Pkcs7Error::SignerNotFound));
+
+        // 只有证书的消息
+        let certs_only = SignedDataBuilder::new(b"").add_certificate(&ca).build().unwrap();
+        assert_eq!(certs_only.certificates(), [ca]);
+        assert_eq!(certs_only.verify(None), Err(Pkcs7Error::SignerNotFound));
+    }
+}
@@ -55,9 +55,9 @@ impl BigInt256 {
 
         let mut limbs = [0u64; 4];
         // 大端到小端转换
-        for i in 0..4 {
+        for (i, limb) in limbs.iter_mut().enumerate() {
             let offset = (3 - i) * 8;
-            limbs[i] = u64::from_be_bytes([
+            *limb = u64::from_be_bytes([
                 padded[offset],
                 padded[offset + 1],
                 padded[offset + 2],
@@ -125,10 +125,10 @@ impl BigInt256 {
         let mut result = [0u64; 4];
         let mut carry = 0u64;
 
-        for i in 0..4 {
+        for (i, r) in result.iter_mut().enumerate() {
             let (sum1, c1) = self.limbs[i].overflowing_add(other.limbs[i]);
             let (sum2, c2) = sum1.overflowing_add(carry);
-            result[i] = sum2;
+            *r = sum2;
             carry = (c1 as u64) + (c2 as u64);
         }
 
@@ -140,10 +140,10 @@ impl BigInt256 {
         let mut result = [0u64; 4];
         let mut borrow = 0u64;
 
-        for i in 0..4 {
+        for (i, r) in result.iter_mut().enumerate() {
             let (diff1, b1) = self.limbs[i].overflowing_sub(other.limbs[i]);
             let (diff2, b2) = diff1.overflowing_sub(borrow);
-            result[i] = diff2;
+            *r = diff2;
             borrow = (b1 as u64) + (b2 as u64);
         }
 
@@ -220,7 +220,7 @@ impl BigInt256 {
     fn sm2_mod_reduce_p(c: &[u64; 8]) -> BigInt256 {
         // 拆分为32位字（小端序）
         let w = |i: usize| -> i64 {
-            if i % 2 == 0 {
+            if i.is_multiple_of(2) {
                 (c[i / 2] & 0xFFFFFFFF) as i64
             } else {
                 (c[i / 2] >> 32) as i64
@@ -251,8 +251,8 @@ impl BigInt256 {
         let mut acc = [0i64; 9]; // 8个输出位 + 1个溢出
         for j in 0..8 {
             acc[j] = w(j);
-            for i in 0..8 {
-                acc[j] += w(i + 8) * R[i][j];
+            for (i, row) in R.iter().enumerate() {
+                acc[j] += w(i + 8) * row[j];
             }
         }
 
@@ -492,10 +492,10 @@ impl BigInt256 {
     /// 右移1位
     pub fn shift_right_1(&self) -> BigInt256 {
         let mut result = [0u64; 4];
-        for i in 0..4 {
-            result[i] = self.limbs[i] >> 1;
+        for (i, r) in result.iter_mut().enumerate() {
+            *r = self.limbs[i] >> 1;
             if i < 3 {
-                result[i] |= self.limbs[i + 1] << 63;
+                *r |= self.limbs[i + 1] << 63;
             }
         }
         BigInt256 { limbs: result }
@@ -554,7 +554,7 @@ impl Default for BigInt256 {
 
 impl PartialOrd for BigInt256 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.compare(other))
+        Some(self.cmp(other))
     }
 }
 
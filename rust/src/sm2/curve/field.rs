@@ -0,0 +1,140 @@
+//! 基域元素，模 p = 2^256 - 2^224 - 2^96 + 2^64 - 1，内部为 Montgomery 形式
+
+#![allow(clippy::assign_op_pattern, clippy::op_ref)]
+
+use core::fmt;
+use core::iter::{Product, Sum};
+use core::ops::{AddAssign, MulAssign, Neg, SubAssign};
+
+use elliptic_curve::bigint::{Limb, U256};
+use elliptic_curve::ff::PrimeField;
+use elliptic_curve::subtle::{Choice, ConstantTimeEq, CtOption};
+
+use super::{FieldBytes, Limbs, Sm2Curve, mont_mul, mont_neg_inv, mont_reduce};
+
+const MODULUS_HEX: &str = "fffffffeffffffffffffffffffffffffffffffff00000000ffffffffffffffff";
+
+pub(super) const MODULUS: U256 = U256::from_be_hex(MODULUS_HEX);
+
+/// R^2 mod p，R = 2^256
+const R_2: U256 = U256::from_be_hex("0000000400000002000000010000000100000002ffffffff0000000200000003");
+
+const NEG_INV: Limb = mont_neg_inv(&MODULUS);
+
+/// p - 2（小端64位分组）
+const P_MINUS_2: [u64; 4] = [0xfffffffffffffffd, 0xffffffff00000000, 0xffffffffffffffff, 0xfffffffeffffffff];
+
+/// (p + 1) / 4
+const P_PLUS_1_DIV_4: [u64; 4] = [0x4000000000000000, 0xffffffffc0000000, 0xffffffffffffffff, 0x3fffffffbfffffff];
+
+/// 基域元素
+#[derive(Clone, Copy)]
+pub struct FieldElement(pub(crate) U256);
+
+primeorder::impl_mont_field_element!(
+    Sm2Curve,
+    FieldElement,
+    FieldBytes,
+    U256,
+    MODULUS,
+    Limbs,
+    fe_from_montgomery,
+    fe_to_montgomery,
+    fe_add,
+    fe_sub,
+    fe_mul,
+    fe_neg,
+    fe_square
+);
+
+impl FieldElement {
+    /// 逆元，零没有逆元
+    pub fn invert(&self) -> CtOption<Self> {
+        CtOption::new(self.invert_unchecked(), !self.is_zero())
+    }
+
+    // 费马小定理：a^(p-2)，指数固定，运算时间与 a 无关
+    const fn invert_unchecked(&self) -> Self {
+        self.pow_vartime(&P_MINUS_2)
+    }
+
+    /// 平方根，p ≡ 3 (mod 4) 时为 a^((p+1)/4)
+    pub fn sqrt(&self) -> CtOption<Self> {
+        let sqrt = self.pow_vartime(&P_PLUS_1_DIV_4);
+        CtOption::new(sqrt, sqrt.square().ct_eq(self))
+    }
+}
+
+impl PrimeField for FieldElement {
+    type Repr = FieldBytes;
+
+    const MODULUS: &'static str = MODULUS_HEX;
+    const NUM_BITS: u32 = 256;
+    const CAPACITY: u32 = 255;
+    const TWO_INV: Self = Self::from_u64(2).invert_unchecked();
+    const MULTIPLICATIVE_GENERATOR: Self = Self::from_u64(13);
+    const S: u32 = 1;
+    // p - 1 = 2 * t，单位根即 -1
+    const ROOT_OF_UNITY: Self = Self::from_hex("fffffffeffffffffffffffffffffffffffffffff00000000fffffffffffffffe");
+    const ROOT_OF_UNITY_INV: Self = Self::ROOT_OF_UNITY;
+    const DELTA: Self = Self::from_u64(169);
+
+    fn from_repr(bytes: FieldBytes) -> CtOption<Self> {
+        Self::from_bytes(&bytes)
+    }
+
+    fn to_repr(&self) -> FieldBytes {
+        self.to_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        self.is_odd()
+    }
+}
+
+impl fmt::Debug for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FieldElement(0x{:X})", self.to_canonical())
+    }
+}
+
+const fn fe_add(a: &Limbs, b: &Limbs) -> Limbs {
+    U256::from_words(*a).add_mod(&U256::from_words(*b), &MODULUS).to_words()
+}
+
+const fn fe_sub(a: &Limbs, b: &Limbs) -> Limbs {
+    U256::from_words(*a).sub_mod(&U256::from_words(*b), &MODULUS).to_words()
+}
+
+const fn fe_neg(a: &Limbs) -> Limbs {
+    U256::from_words(*a).neg_mod(&MODULUS).to_words()
+}
+
+const fn fe_mul(a: &Limbs, b: &Limbs) -> Limbs {
+    mont_mul(a, b, &MODULUS, NEG_INV)
+}
+
+const fn fe_square(a: &Limbs) -> Limbs {
+    mont_mul(a, a, &MODULUS, NEG_INV)
+}
+
+const fn fe_to_montgomery(a: &Limbs) -> Limbs {
+    mont_mul(a, R_2.as_words(), &MODULUS, NEG_INV)
+}
+
+const fn fe_from_montgomery(a: &Limbs) -> Limbs {
+    mont_reduce(a, &MODULUS, NEG_INV)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (p - 1) / 2
+    const T: [u64; 4] = [0x7fffffffffffffff, 0xffffffff80000000, 0xffffffffffffffff, 0x7fffffff7fffffff];
+
+    primeorder::impl_field_identity_tests!(FieldElement);
+    primeorder::impl_field_invert_tests!(FieldElement);
+    primeorder::impl_field_sqrt_tests!(FieldElement);
+    primeorder::impl_primefield_tests!(FieldElement, T);
+}
@@ -0,0 +1,189 @@
+//! SM2 曲线的 `elliptic-curve` / `primeorder` trait 实现
+//!
+//! y^2 = x^3 + ax + b (mod p)，a = p - 3，点运算沿用 primeorder 中 a = -3 的完备公式。
+//! 基域与标量域元素均为 Montgomery 形式，常量可在编译期求值。
+
+mod field;
+mod scalar;
+
+pub use field::FieldElement;
+pub use scalar::Scalar;
+
+use elliptic_curve::bigint::modular::montgomery_reduction;
+use elliptic_curve::bigint::{ArrayEncoding, Limb, U256, Word};
+use elliptic_curve::consts::U32;
+use elliptic_curve::sec1::ToEncodedPoint;
+use elliptic_curve::{Curve, CurveArithmetic, FieldBytesEncoding, PrimeCurve, PrimeCurveArithmetic};
+use primeorder::{PrimeCurveParams, point_arithmetic};
+
+use super::signing::{DEFAULT_DISTID, SM2Error, SigningKey, VerifyingKey};
+
+/// SM2 推荐曲线 sm2p256v1
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Sm2Curve;
+
+/// 32字节大端编码的域元素或标量
+pub type FieldBytes = elliptic_curve::FieldBytes<Sm2Curve>;
+
+pub type AffinePoint = primeorder::AffinePoint<Sm2Curve>;
+
+pub type ProjectivePoint = primeorder::ProjectivePoint<Sm2Curve>;
+
+pub type NonZeroScalar = elliptic_curve::NonZeroScalar<Sm2Curve>;
+
+pub type PublicKey = elliptic_curve::PublicKey<Sm2Curve>;
+
+pub type SecretKey = elliptic_curve::SecretKey<Sm2Curve>;
+
+impl Curve for Sm2Curve {
+    type FieldBytesSize = U32;
+    type Uint = U256;
+
+    const ORDER: U256 = scalar::MODULUS;
+}
+
+impl PrimeCurve for Sm2Curve {}
+
+impl CurveArithmetic for Sm2Curve {
+    type AffinePoint = AffinePoint;
+    type ProjectivePoint = ProjectivePoint;
+    type Scalar = Scalar;
+}
+
+impl PrimeCurveArithmetic for Sm2Curve {
+    type CurveGroup = ProjectivePoint;
+}
+
+impl PrimeCurveParams for Sm2Curve {
+    type FieldElement = FieldElement;
+    type PointArithmetic = point_arithmetic::EquationAIsMinusThree;
+
+    /// a = -3
+    const EQUATION_A: FieldElement = FieldElement::from_u64(3).neg();
+
+    const EQUATION_B: FieldElement =
+        FieldElement::from_hex("28e9fa9e9d9f5e344d5a9e4bcf6509a7f39789f515ab8f92ddbcbd414d940e93");
+
+    const GENERATOR: (FieldElement, FieldElement) = (
+        FieldElement::from_hex("32c4ae2c1f1981195f9904466a39c9948fe30bbff2660be1715a4589334c74c7"),
+        FieldElement::from_hex("bc3736a2f4f6779c59bdcee36b692153d0a9877cc62a474002df32e52139f0a0"),
+    );
+}
+
+impl elliptic_curve::point::PointCompression for Sm2Curve {
+    const COMPRESS_POINTS: bool = false;
+}
+
+impl FieldBytesEncoding<Sm2Curve> for U256 {
+    fn decode_field_bytes(field_bytes: &FieldBytes) -> Self {
+        U256::from_be_byte_array(*field_bytes)
+    }
+
+    fn encode_field_bytes(&self) -> FieldBytes {
+        self.to_be_byte_array()
+    }
+}
+
+impl From<&VerifyingKey> for PublicKey {
+    fn from(key: &VerifyingKey) -> Self {
+        // VerifyingKey 已保证点在曲线上
+        PublicKey::from_sec1_bytes(&key.to_sec1_bytes()).unwrap()
+    }
+}
+
+/// 转换后使用默认ID
+impl From<&PublicKey> for VerifyingKey {
+    fn from(key: &PublicKey) -> Self {
+        let point = key.to_encoded_point(false);
+        let point = super::point::ECPoint::from_encoded(point.as_bytes());
+        VerifyingKey::from_point(point, DEFAULT_DISTID)
+    }
+}
+
+impl From<&SigningKey> for SecretKey {
+    fn from(key: &SigningKey) -> Self {
+        SecretKey::from_bytes(&key.to_bytes().into()).unwrap()
+    }
+}
+
+/// 转换后使用默认ID；d = n-1 不能用于签名，返回错误
+impl TryFrom<&SecretKey> for SigningKey {
+    type Error = SM2Error;
+
+    fn try_from(key: &SecretKey) -> Result<Self, SM2Error> {
+        SigningKey::from_bytes(&key.to_bytes().into())
+    }
+}
+
+type Limbs = [Word; U256::LIMBS];
+
+/// -m^-1 mod 2^w，m 为奇数
+const fn mont_neg_inv(m: &U256) -> Limb {
+    Limb(m.inv_mod2k(Word::BITS as usize).as_words()[0].wrapping_neg())
+}
+
+/// Montgomery 乘法 a * b * R^-1 mod m
+const fn mont_mul(a: &Limbs, b: &Limbs, m: &U256, neg_inv: Limb) -> Limbs {
+    let wide = U256::from_words(*a).mul_wide(&U256::from_words(*b));
+    montgomery_reduction(&wide, m, neg_inv).to_words()
+}
+
+/// a * R^-1 mod m
+const fn mont_reduce(a: &Limbs, m: &U256, neg_inv: Limb) -> Limbs {
+    montgomery_reduction(&(U256::from_words(*a), U256::ZERO), m, neg_inv).to_words()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm2::bigint256::BigInt256;
+    use crate::sm2::point::ECPoint;
+    use elliptic_curve::group::Group;
+    use elliptic_curve::ops::Reduce;
+
+    fn to_ecpoint(p: &ProjectivePoint) -> ECPoint {
+        ECPoint::from_encoded(p.to_affine().to_encoded_point(false).as_bytes())
+    }
+
+    #[test]
+    fn test_generator() {
+        let g = ProjectivePoint::GENERATOR;
+        assert_eq!(to_ecpoint(&g), ECPoint::generator());
+        // [n-1]G = -G，[n]G = O
+        assert_eq!(g * -Scalar::ONE, -g);
+        assert!(bool::from((g * -Scalar::ONE + g).is_identity()));
+    }
+
+    #[test]
+    fn test_scalar_mul_matches_ecpoint() {
+        let k_bytes = [0x5a; 32];
+        let k = Scalar::reduce_bytes(&k_bytes.into());
+        let expected = ECPoint::generator().multiply(&BigInt256::from_be_bytes(&k_bytes));
+        assert_eq!(to_ecpoint(&(ProjectivePoint::GENERATOR * k)), expected);
+
+        // 点加、倍点与 ECPoint 一致
+        let p = ProjectivePoint::GENERATOR * Scalar::from(7u64);
+        let q = ProjectivePoint::GENERATOR * Scalar::from(11u64);
+        let (ep, eq) = (to_ecpoint(&p), to_ecpoint(&q));
+        assert_eq!(to_ecpoint(&(p + q)), ep.add(&eq));
+        assert_eq!(to_ecpoint(&p.double()), ep.twice());
+
+        let r = Scalar::reduce_bytes(&[0xc3; 32].into());
+        assert_eq!(ProjectivePoint::GENERATOR * r * r.invert().unwrap(), ProjectivePoint::GENERATOR);
+    }
+
+    #[test]
+    fn test_key_conversions() {
+        let sk = SigningKey::random().with_distid(b"alice").unwrap();
+        let secret = SecretKey::from(&sk);
+        let public = PublicKey::from(sk.verifying_key());
+        assert_eq!(secret.public_key(), public);
+
+        // 经 elliptic-curve 类型转换后回到默认ID
+        let back = SigningKey::try_from(&secret).unwrap();
+        assert_eq!(back.to_bytes(), sk.to_bytes());
+        assert_eq!(back.distid(), DEFAULT_DISTID);
+        let vk = VerifyingKey::from(&public);
+        assert!(vk.verify(b"msg", &back.sign(b"msg")));
+    }
+}
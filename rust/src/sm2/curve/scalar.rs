@@ -0,0 +1,249 @@
+//! 标量域元素，模曲线阶 n，内部为 Montgomery 形式
+
+#![allow(clippy::assign_op_pattern, clippy::op_ref)]
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::{Product, Sum};
+use core::ops::{AddAssign, MulAssign, Neg, ShrAssign, SubAssign};
+
+use elliptic_curve::bigint::{Limb, U256};
+use elliptic_curve::ff::PrimeField;
+use elliptic_curve::ops::{Invert, Reduce};
+use elliptic_curve::scalar::{FromUintUnchecked, IsHigh};
+use elliptic_curve::subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, CtOption};
+use elliptic_curve::{FieldBytesEncoding, ScalarPrimitive};
+
+use super::{FieldBytes, Limbs, Sm2Curve, mont_mul, mont_neg_inv, mont_reduce};
+
+const MODULUS_HEX: &str = "fffffffeffffffffffffffffffffffff7203df6b21c6052b53bbf40939d54123";
+
+pub(super) const MODULUS: U256 = U256::from_be_hex(MODULUS_HEX);
+
+/// (n - 1) / 2，大于它的标量为“高位”
+const FRAC_MODULUS_2: U256 = MODULUS.shr_vartime(1);
+
+/// R^2 mod n，R = 2^256
+const R_2: U256 = U256::from_be_hex("1eb5e412a22b3d3b620fc84c3affe0d43464504ade6fa2fa901192af7c114f20");
+
+const NEG_INV: Limb = mont_neg_inv(&MODULUS);
+
+/// n - 2（小端64位分组）
+const N_MINUS_2: [u64; 4] = [0x53bbf40939d54121, 0x7203df6b21c6052b, 0xffffffffffffffff, 0xfffffffeffffffff];
+
+/// (n + 1) / 4
+const N_PLUS_1_DIV_4: [u64; 4] = [0xd4eefd024e755049, 0xdc80f7dac871814a, 0xffffffffffffffff, 0x3fffffffbfffffff];
+
+/// 标量
+#[derive(Clone, Copy)]
+pub struct Scalar(pub(crate) U256);
+
+primeorder::impl_mont_field_element!(
+    Sm2Curve,
+    Scalar,
+    FieldBytes,
+    U256,
+    MODULUS,
+    Limbs,
+    sc_from_montgomery,
+    sc_to_montgomery,
+    sc_add,
+    sc_sub,
+    sc_mul,
+    sc_neg,
+    sc_square
+);
+
+impl Scalar {
+    /// 逆元，零没有逆元
+    pub fn invert(&self) -> CtOption<Self> {
+        CtOption::new(self.invert_unchecked(), !self.is_zero())
+    }
+
+    // 费马小定理：a^(n-2)
+    const fn invert_unchecked(&self) -> Self {
+        self.pow_vartime(&N_MINUS_2)
+    }
+
+    /// 平方根，n ≡ 3 (mod 4) 时为 a^((n+1)/4)
+    pub fn sqrt(&self) -> CtOption<Self> {
+        let sqrt = self.pow_vartime(&N_PLUS_1_DIV_4);
+        CtOption::new(sqrt, sqrt.square().ct_eq(self))
+    }
+}
+
+impl PrimeField for Scalar {
+    type Repr = FieldBytes;
+
+    const MODULUS: &'static str = MODULUS_HEX;
+    const NUM_BITS: u32 = 256;
+    const CAPACITY: u32 = 255;
+    const TWO_INV: Self = Self::from_u64(2).invert_unchecked();
+    const MULTIPLICATIVE_GENERATOR: Self = Self::from_u64(3);
+    const S: u32 = 1;
+    // n - 1 = 2 * t，单位根即 -1
+    const ROOT_OF_UNITY: Self = Self::from_hex("fffffffeffffffffffffffffffffffff7203df6b21c6052b53bbf40939d54122");
+    const ROOT_OF_UNITY_INV: Self = Self::ROOT_OF_UNITY;
+    const DELTA: Self = Self::from_u64(9);
+
+    fn from_repr(bytes: FieldBytes) -> CtOption<Self> {
+        Self::from_bytes(&bytes)
+    }
+
+    fn to_repr(&self) -> FieldBytes {
+        self.to_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        self.is_odd()
+    }
+}
+
+impl fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Scalar(0x{:X})", self.to_canonical())
+    }
+}
+
+impl AsRef<Scalar> for Scalar {
+    fn as_ref(&self) -> &Scalar {
+        self
+    }
+}
+
+impl FromUintUnchecked for Scalar {
+    type Uint = U256;
+
+    fn from_uint_unchecked(uint: U256) -> Self {
+        Self::from_uint_unchecked(uint)
+    }
+}
+
+impl Invert for Scalar {
+    type Output = CtOption<Self>;
+
+    fn invert(&self) -> CtOption<Self> {
+        self.invert()
+    }
+}
+
+impl IsHigh for Scalar {
+    fn is_high(&self) -> Choice {
+        self.to_canonical().ct_gt(&FRAC_MODULUS_2)
+    }
+}
+
+impl Reduce<U256> for Scalar {
+    type Bytes = FieldBytes;
+
+    // n > 2^255，一次条件减法即可
+    fn reduce(w: U256) -> Self {
+        let (r, borrow) = w.sbb(&MODULUS, Limb::ZERO);
+        let underflow = Choice::from((borrow.0 >> (Limb::BITS - 1)) as u8);
+        Self::from_uint_unchecked(U256::conditional_select(&r, &w, underflow))
+    }
+
+    fn reduce_bytes(bytes: &FieldBytes) -> Self {
+        Self::reduce(FieldBytesEncoding::<Sm2Curve>::decode_field_bytes(bytes))
+    }
+}
+
+impl ShrAssign<usize> for Scalar {
+    fn shr_assign(&mut self, rhs: usize) {
+        *self = Self::from_uint_unchecked(self.to_canonical().shr_vartime(rhs));
+    }
+}
+
+impl PartialOrd for Scalar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scalar {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_canonical().cmp(&other.to_canonical())
+    }
+}
+
+impl From<ScalarPrimitive<Sm2Curve>> for Scalar {
+    fn from(scalar: ScalarPrimitive<Sm2Curve>) -> Self {
+        Self::from_uint_unchecked(*scalar.as_uint())
+    }
+}
+
+impl From<Scalar> for ScalarPrimitive<Sm2Curve> {
+    fn from(scalar: Scalar) -> Self {
+        // 规范形式必小于 n
+        ScalarPrimitive::new(scalar.to_canonical()).unwrap()
+    }
+}
+
+impl From<Scalar> for FieldBytes {
+    fn from(scalar: Scalar) -> Self {
+        scalar.to_bytes()
+    }
+}
+
+impl From<Scalar> for U256 {
+    fn from(scalar: Scalar) -> Self {
+        scalar.to_canonical()
+    }
+}
+
+const fn sc_add(a: &Limbs, b: &Limbs) -> Limbs {
+    U256::from_words(*a).add_mod(&U256::from_words(*b), &MODULUS).to_words()
+}
+
+const fn sc_sub(a: &Limbs, b: &Limbs) -> Limbs {
+    U256::from_words(*a).sub_mod(&U256::from_words(*b), &MODULUS).to_words()
+}
+
+const fn sc_neg(a: &Limbs) -> Limbs {
+    U256::from_words(*a).neg_mod(&MODULUS).to_words()
+}
+
+const fn sc_mul(a: &Limbs, b: &Limbs) -> Limbs {
+    mont_mul(a, b, &MODULUS, NEG_INV)
+}
+
+const fn sc_square(a: &Limbs) -> Limbs {
+    mont_mul(a, a, &MODULUS, NEG_INV)
+}
+
+const fn sc_to_montgomery(a: &Limbs) -> Limbs {
+    mont_mul(a, R_2.as_words(), &MODULUS, NEG_INV)
+}
+
+const fn sc_from_montgomery(a: &Limbs) -> Limbs {
+    mont_reduce(a, &MODULUS, NEG_INV)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (n - 1) / 2
+    const T: [u64; 4] = [0xa9ddfa049ceaa091, 0xb901efb590e30295, 0xffffffffffffffff, 0x7fffffff7fffffff];
+
+    primeorder::impl_field_identity_tests!(Scalar);
+    primeorder::impl_field_invert_tests!(Scalar);
+    primeorder::impl_field_sqrt_tests!(Scalar);
+    primeorder::impl_primefield_tests!(Scalar, T);
+
+    #[test]
+    fn test_reduce_and_ordering() {
+        assert_eq!(Scalar::reduce(MODULUS), Scalar::ZERO);
+        assert_eq!(Scalar::reduce(MODULUS.wrapping_add(&U256::from_u8(5))), Scalar::from(5u64));
+        assert_eq!(Scalar::reduce(U256::MAX), Scalar::from_uint_unchecked(U256::MAX.wrapping_sub(&MODULUS)));
+
+        let high = -Scalar::ONE;
+        assert!(bool::from(high.is_high()));
+        assert!(!bool::from(Scalar::ONE.is_high()));
+        assert!(Scalar::ONE < high);
+
+        let mut x = Scalar::from(10u64);
+        x >>= 1;
+        assert_eq!(x, Scalar::from(5u64));
+    }
+}
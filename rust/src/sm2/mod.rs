@@ -1,12 +1,21 @@
 // SM2椭圆曲线公钥密码算法实现
 
 pub mod bigint256;
+#[cfg(feature = "elliptic-curve")]
+pub mod curve;
+mod encryption;
 pub mod fp;
 pub mod point;
+mod pkcs8;
+pub mod signing;
+mod spki;
 
 use bigint256::BigInt256;
 use point::{ECPoint, SM2_N};
+pub use signing::{DEFAULT_DISTID, SM2Error, Signature, SigningKey, VerifyingKey};
+use crate::kdf::sm3_kdf_parts;
 use crate::sm3::Sm3;
+use crate::util::getrandom;
 
 /// SM2密钥交换协议参数
 #[derive(Clone, Debug)]
@@ -43,10 +52,10 @@ pub struct SM2;
 
 impl SM2 {
     /// 生成密钥对
-    /// 返回 (私钥hex, 公钥hex)
+    /// 返回 (私钥hex, 公钥hex)，系统随机源不可用时 panic
     pub fn gen_key_pair() -> (String, String) {
         loop {
-            let private_key = Self::random_bigint();
+            let private_key = Self::random_bigint().expect("system random number generator unavailable");
 
             // 确保私钥在有效范围 [1, n-1]
             if private_key.is_zero() || private_key.compare(&SM2_N) != core::cmp::Ordering::Less {
@@ -77,9 +86,46 @@ impl SM2 {
             return Err("Invalid public key".to_string());
         }
 
+        let (c1, c3, c2) = Self::encrypt_raw(message, &pub_point).map_err(|e| e.to_string())?;
+
+        // 输出 C1 || C3 || C2
+        let mut result = String::new();
+        result.push_str(&c1.to_hex_encoded());
+        result.push_str(&bytes_to_hex(&c3));
+        result.push_str(&bytes_to_hex(&c2));
+        Ok(result)
+    }
+
+    /// 私钥解密
+    pub fn decrypt(ciphertext: &str, private_key: &str) -> Result<String, String> {
+        if ciphertext.len() < 130 + 64 {
+            return Err("Ciphertext too short".to_string());
+        }
+
+        // 解析 C1 || C3 || C2
+        let c1_hex = &ciphertext[0..130];
+        let c3_hex = &ciphertext[130..194];
+        let c2_hex = &ciphertext[194..];
+
+        let c1 = ECPoint::from_hex_encoded(c1_hex);
+        if !c1.is_on_curve() {
+            return Err("Invalid C1 point".to_string());
+        }
+
+        let c3 = hex_to_bytes(c3_hex)?;
+        let c2 = hex_to_bytes(c2_hex)?;
+
+        let d = BigInt256::from_hex(private_key);
+        let message = Self::decrypt_raw(&c1, &c3, &c2, &d)?;
+
+        String::from_utf8(message).map_err(|e| format!("UTF-8 decode error: {}", e))
+    }
+
+    /// 加密核心，返回 (C1, C3, C2)；`message` 不能为空，公钥须已验证在曲线上
+    fn encrypt_raw(message: &[u8], pub_point: &ECPoint) -> Result<(ECPoint, [u8; 32], Vec<u8>), SM2Error> {
         loop {
             // 生成随机数 k
-            let k = Self::random_bigint();
+            let k = Self::random_bigint()?;
             if k.is_zero() || k.compare(&SM2_N) != core::cmp::Ordering::Less {
                 continue;
             }
@@ -113,41 +159,15 @@ impl SM2 {
             sm3.update(message);
             sm3.update(&p2.y.to_be_bytes());
             sm3.finish();
-            let c3 = sm3.hash_bytes();
 
-            // 输出 C1 || C3 || C2
-            let mut result = String::new();
-            result.push_str(&c1.to_hex_encoded());
-            result.push_str(&bytes_to_hex(c3));
-            result.push_str(&bytes_to_hex(&c2));
-
-            return Ok(result);
+            return Ok((c1, *sm3.hash_bytes(), c2));
         }
     }
 
-    /// 私钥解密
-    pub fn decrypt(ciphertext: &str, private_key: &str) -> Result<String, String> {
-        if ciphertext.len() < 130 + 64 {
-            return Err("Ciphertext too short".to_string());
-        }
-
-        // 解析 C1 || C3 || C2
-        let c1_hex = &ciphertext[0..130];
-        let c3_hex = &ciphertext[130..194];
-        let c2_hex = &ciphertext[194..];
-
-        let c1 = ECPoint::from_hex_encoded(c1_hex);
-        if !c1.is_on_curve() {
-            return Err("Invalid C1 point".to_string());
-        }
-
-        let c3 = hex_to_bytes(c3_hex)?;
-        let mut c2 = hex_to_bytes(c2_hex)?;
-
-        let d = BigInt256::from_hex(private_key);
-
+    /// 解密核心，C1 须已验证在曲线上
+    fn decrypt_raw(c1: &ECPoint, c3: &[u8], c2: &[u8], d: &BigInt256) -> Result<Vec<u8>, String> {
         // P2 = [d]C1
-        let p2 = c1.multiply(&d);
+        let p2 = c1.multiply(d);
         if p2.is_infinity() {
             return Err("Invalid decryption".to_string());
         }
@@ -156,23 +176,24 @@ impl SM2 {
         let key = Self::kdf(c2.len(), &p2);
 
         // M = C2 XOR t
-        for i in 0..c2.len() {
-            c2[i] ^= key[i];
+        let mut message = c2.to_vec();
+        for i in 0..message.len() {
+            message[i] ^= key[i];
         }
 
         // 验证 C3
         let mut sm3 = Sm3::new();
         sm3.update(&p2.x.to_be_bytes());
-        sm3.update(&c2);
+        sm3.update(&message);
         sm3.update(&p2.y.to_be_bytes());
         sm3.finish();
         let computed_c3 = sm3.hash_bytes();
 
-        if computed_c3.as_slice() != c3.as_slice() {
+        if computed_c3.as_slice() != c3 {
             return Err("Decryption verification failed".to_string());
         }
 
-        String::from_utf8(c2).map_err(|e| format!("UTF-8 decode error: {}", e))
+        Ok(message)
     }
 
     /// 签名
@@ -183,16 +204,46 @@ impl SM2 {
         // 计算 Z
         let z = Self::user_sm3_z(user_id.as_bytes(), &public_key);
 
-        // e = SM3(Z || M)
+        let e = Self::message_digest(&z, message.as_bytes());
+        let (r, s) = Self::sign_prehash(&d, &e).map_err(|e| e.to_string())?;
+        Ok(format!("{}h{}", r.to_hex_lower(), s.to_hex_lower()))
+    }
+
+    /// 验签
+    pub fn verify(user_id: &str, signature: &str, message: &str, public_key: &str) -> bool {
+        let parts: Vec<&str> = signature.split('h').collect();
+        if parts.len() != 2 {
+            return false;
+        }
+
+        let r = BigInt256::from_hex(parts[0]);
+        let s = BigInt256::from_hex(parts[1]);
+
+        let pub_point = ECPoint::from_hex_encoded(public_key);
+        if !pub_point.is_on_curve() {
+            return false;
+        }
+
+        // 计算 Z
+        let z = Self::user_sm3_z(user_id.as_bytes(), &pub_point);
+
+        let e = Self::message_digest(&z, message.as_bytes());
+        Self::verify_prehash(&pub_point, &e, &r, &s)
+    }
+
+    /// e = SM3(Z || M)
+    fn message_digest(z: &[u8], message: &[u8]) -> BigInt256 {
         let mut sm3 = Sm3::new();
-        sm3.update(&z);
-        sm3.update(message.as_bytes());
-        sm3.finish();
-        let e = BigInt256::from_be_bytes(sm3.hash_bytes());
+        sm3.update(z);
+        sm3.update(message);
+        BigInt256::from_be_bytes(&sm3.finalize())
+    }
 
+    /// 对摘要 e 签名，返回 (r, s)
+    fn sign_prehash(d: &BigInt256, e: &BigInt256) -> Result<(BigInt256, BigInt256), SM2Error> {
         loop {
             // 生成随机数 k
-            let k = Self::random_bigint();
+            let k = Self::random_bigint()?;
             if k.is_zero() || k.compare(&SM2_N) != core::cmp::Ordering::Less {
                 continue;
             }
@@ -217,7 +268,7 @@ impl SM2 {
             let one = BigInt256::ONE;
             let (d_plus_1, _) = d.add(&one);
             let d_plus_1_inv = d_plus_1.mod_inverse(&SM2_N);
-            let rd = r.mod_mul(&d, &SM2_N);
+            let rd = r.mod_mul(d, &SM2_N);
             let k_minus_rd = k.mod_sub(&rd, &SM2_N);
             let s = k_minus_rd.mod_mul(&d_plus_1_inv, &SM2_N);
 
@@ -225,25 +276,12 @@ impl SM2 {
                 continue;
             }
 
-            // 确保 r 和 s 都是64个十六进制字符
-            let r_hex = r.to_hex();
-            let s_hex = s.to_hex();
-            if r_hex.len() == 64 && s_hex.len() == 64 {
-                return Ok(format!("{}h{}", r_hex.to_lowercase(), s_hex.to_lowercase()));
-            }
+            return Ok((r, s));
         }
     }
 
-    /// 验签
-    pub fn verify(user_id: &str, signature: &str, message: &str, public_key: &str) -> bool {
-        let parts: Vec<&str> = signature.split('h').collect();
-        if parts.len() != 2 {
-            return false;
-        }
-
-        let r = BigInt256::from_hex(parts[0]);
-        let s = BigInt256::from_hex(parts[1]);
-
+    /// 验证摘要 e 的签名，公钥须已确认在曲线上
+    fn verify_prehash(pub_point: &ECPoint, e: &BigInt256, r: &BigInt256, s: &BigInt256) -> bool {
         // 验证 r, s 在 [1, n-1] 范围内
         if r.is_zero() || r.compare(&SM2_N) != core::cmp::Ordering::Less {
             return false;
@@ -252,29 +290,14 @@ impl SM2 {
             return false;
         }
 
-        let pub_point = ECPoint::from_hex_encoded(public_key);
-        if !pub_point.is_on_curve() {
-            return false;
-        }
-
-        // 计算 Z
-        let z = Self::user_sm3_z(user_id.as_bytes(), &pub_point);
-
-        // e = SM3(Z || M)
-        let mut sm3 = Sm3::new();
-        sm3.update(&z);
-        sm3.update(message.as_bytes());
-        sm3.finish();
-        let e = BigInt256::from_be_bytes(sm3.hash_bytes());
-
         // t = (r + s) mod n
-        let t = r.mod_add(&s, &SM2_N);
+        let t = r.mod_add(s, &SM2_N);
         if t.is_zero() {
             return false;
         }
 
         // (x1, y1) = [s]G + [t]PA
-        let sg = ECPoint::generator().multiply(&s);
+        let sg = ECPoint::generator().multiply(s);
         let tpa = pub_point.multiply(&t);
         let point = sg.add(&tpa);
 
@@ -285,10 +308,11 @@ impl SM2 {
         // R = (e + x1) mod n
         let computed_r = e.mod_add(&point.x.to_bigint(), &SM2_N);
 
-        r == computed_r
+        *r == computed_r
     }
 
     /// B用户密钥交换
+    #[allow(clippy::too_many_arguments)]
     pub fn get_sb(
         byte_len: usize,
         p_a: &ECPoint,
@@ -341,6 +365,7 @@ impl SM2 {
     }
 
     /// A用户密钥交换
+    #[allow(clippy::too_many_arguments)]
     pub fn get_sa(
         byte_len: usize,
         p_b: &ECPoint,
@@ -423,61 +448,25 @@ impl SM2 {
     // ============ 内部辅助方法 ============
 
     /// 生成随机256位整数
-    fn random_bigint() -> BigInt256 {
+    ///
+    /// 密钥与签名随机数不能退化为可预测的值，系统随机源不可用时返回 [`SM2Error::RandomUnavailable`]
+    fn random_bigint() -> Result<BigInt256, SM2Error> {
         let mut bytes = [0u8; 32];
-        getrandom(&mut bytes);
-        BigInt256::from_be_bytes(&bytes)
+        getrandom(&mut bytes).map_err(|_| SM2Error::RandomUnavailable)?;
+        Ok(BigInt256::from_be_bytes(&bytes))
     }
 
-    /// KDF密钥派生函数（用于加密）
+    /// KDF密钥派生函数（用于加密），Z = x2 || y2
     fn kdf(keylen: usize, p2: &ECPoint) -> Vec<u8> {
         let mut result = vec![0u8; keylen];
-        let mut ct = 1u32;
-        let blocks = (keylen + 31) / 32;
-
-        for i in 0..blocks {
-            let mut sm3 = Sm3::new();
-            sm3.update(&p2.x.to_be_bytes());
-            sm3.update(&p2.y.to_be_bytes());
-            sm3.update(&ct.to_be_bytes());
-            sm3.finish();
-            let hash = sm3.hash_bytes();
-
-            let start = i * 32;
-            let end = ((i + 1) * 32).min(keylen);
-            let copy_len = end - start;
-            result[start..start + copy_len].copy_from_slice(&hash[..copy_len]);
-
-            ct += 1;
-        }
-
+        sm3_kdf_parts(&[&p2.x.to_be_bytes(), &p2.y.to_be_bytes()], &mut result);
         result
     }
 
-    /// KDF密钥派生函数（用于密钥交换）
+    /// KDF密钥派生函数（用于密钥交换），Z = xV || yV || ZA || ZB
     fn kdf_key_swap(keylen: usize, vu: &ECPoint, za: &[u8], zb: &[u8]) -> Vec<u8> {
         let mut result = vec![0u8; keylen];
-        let mut ct = 1u32;
-        let blocks = (keylen + 31) / 32;
-
-        for i in 0..blocks {
-            let mut sm3 = Sm3::new();
-            sm3.update(&vu.x.to_be_bytes());
-            sm3.update(&vu.y.to_be_bytes());
-            sm3.update(za);
-            sm3.update(zb);
-            sm3.update(&ct.to_be_bytes());
-            sm3.finish();
-            let hash = sm3.hash_bytes();
-
-            let start = i * 32;
-            let end = ((i + 1) * 32).min(keylen);
-            let copy_len = end - start;
-            result[start..start + copy_len].copy_from_slice(&hash[..copy_len]);
-
-            ct += 1;
-        }
-
+        sm3_kdf_parts(&[&vu.x.to_be_bytes(), &vu.y.to_be_bytes(), za, zb], &mut result);
         result
     }
 
@@ -576,7 +565,7 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
 }
 
 fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return Err("Invalid hex string length".to_string());
     }
     let mut bytes = Vec::with_capacity(hex.len() / 2);
@@ -588,58 +577,6 @@ fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
     Ok(bytes)
 }
 
-/// 使用系统随机数生成器填充字节数组
-fn getrandom(dest: &mut [u8]) {
-    #[cfg(target_family = "unix")]
-    {
-        use std::fs::File;
-        use std::io::Read;
-
-        if let Ok(mut f) = File::open("/dev/urandom") {
-            let _ = f.read_exact(dest);
-            return;
-        }
-    }
-
-    #[cfg(target_family = "windows")]
-    {
-        extern "system" {
-            fn BCryptGenRandom(
-                hAlgorithm: *mut core::ffi::c_void,
-                pbBuffer: *mut u8,
-                cbBuffer: u32,
-                dwFlags: u32,
-            ) -> i32;
-        }
-
-        unsafe {
-            BCryptGenRandom(
-                core::ptr::null_mut(),
-                dest.as_mut_ptr(),
-                dest.len() as u32,
-                2, // BCRYPT_USE_SYSTEM_PREFERRED_RNG
-            );
-        }
-        return;
-    }
-
-    #[cfg(target_family = "wasm")]
-    {
-        // WASM环境下使用简单的伪随机数生成
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let seed = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64;
-
-        let mut state = seed;
-        for b in dest.iter_mut() {
-            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
-            *b = (state >> 33) as u8;
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
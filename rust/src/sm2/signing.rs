@@ -0,0 +1,483 @@
+//! SM2 数字签名的类型化接口
+//!
+//! 签名为 (r, s) 两个32字节大端整数。用户区分标识（ID）参与计算 Z 值，作为密钥的参数保存，
+//! 未指定时使用 GM/T 0009 规定的默认值 `1234567812345678`；签名方与验签方必须使用相同ID。
+
+use std::error::Error;
+use std::fmt;
+
+use crate::der::{self, Reader};
+
+use super::bigint256::BigInt256;
+use super::fp::SM2_P;
+use super::point::{ECPoint, SM2_N};
+use super::{SM2, hex_to_bytes};
+
+/// 默认用户区分标识
+pub const DEFAULT_DISTID: &[u8] = b"1234567812345678";
+
+/// ENTL 为16位比特长度，ID最多 8191 字节
+const MAX_DISTID_LEN: usize = 0xFFFF / 8;
+
+/// 签名与密钥相关错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SM2Error {
+    InvalidPrivateKey,
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidDistid,
+    InvalidEncoding,
+    UnsupportedAlgorithm,
+    DecryptionFailed,
+    EmptyPlaintext,
+    /// 系统随机数生成器不可用
+    RandomUnavailable,
+}
+
+impl fmt::Display for SM2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SM2Error::InvalidPrivateKey => write!(f, "Invalid SM2 private key"),
+            SM2Error::InvalidPublicKey => write!(f, "Invalid SM2 public key"),
+            SM2Error::InvalidSignature => write!(f, "Invalid SM2 signature encoding"),
+            SM2Error::InvalidDistid => write!(f, "Distinguishing identifier is too long"),
+            SM2Error::InvalidEncoding => write!(f, "Malformed key encoding"),
+            SM2Error::UnsupportedAlgorithm => write!(f, "Key is not an SM2 key"),
+            SM2Error::DecryptionFailed => write!(f, "Decryption failed: wrong key, password or corrupted data"),
+            SM2Error::EmptyPlaintext => write!(f, "Plaintext cannot be empty"),
+            SM2Error::RandomUnavailable => write!(f, "Secure random number generator unavailable"),
+        }
+    }
+}
+
+impl Error for SM2Error {}
+
+/// SM2签名 (r, s)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    r: [u8; 32],
+    s: [u8; 32],
+}
+
+impl Signature {
+    /// 由 r、s 构造，两者都必须在 [1, n-1] 内
+    pub fn from_scalars(r: &[u8; 32], s: &[u8; 32]) -> Result<Self, SM2Error> {
+        for v in [r, s] {
+            let v = BigInt256::from_be_bytes(v);
+            if v.is_zero() || v.compare(&SM2_N) != core::cmp::Ordering::Less {
+                return Err(SM2Error::InvalidSignature);
+            }
+        }
+        Ok(Signature { r: *r, s: *s })
+    }
+
+    /// 解析 r || s 共64字节
+    pub fn from_bytes(bytes: &[u8; 64]) -> Result<Self, SM2Error> {
+        let (r, s) = bytes.split_at(32);
+        Self::from_scalars(r.try_into().unwrap(), s.try_into().unwrap())
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, SM2Error> {
+        let bytes: &[u8; 64] = bytes.try_into().map_err(|_| SM2Error::InvalidSignature)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// 编码为 r || s
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&self.r);
+        out[32..].copy_from_slice(&self.s);
+        out
+    }
+
+    /// 解析 DER 编码的 `SEQUENCE { r INTEGER, s INTEGER }`（GM/T 0009，证书与 CMS 中使用）
+    pub fn from_der(bytes: &[u8]) -> Result<Self, SM2Error> {
+        let parse = || -> Result<([u8; 32], [u8; 32]), der::DerError> {
+            let mut outer = Reader::new(bytes);
+            let mut seq = outer.sequence()?;
+            outer.finish()?;
+            let mut out = [[0u8; 32]; 2];
+            for v in &mut out {
+                let int = seq.uint()?;
+                if int.len() > 32 {
+                    return Err(der::DerError);
+                }
+                v[32 - int.len()..].copy_from_slice(int);
+            }
+            seq.finish()?;
+            Ok((out[0], out[1]))
+        };
+        let (r, s) = parse().map_err(|_| SM2Error::InvalidSignature)?;
+        Self::from_scalars(&r, &s)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        der::sequence(&[&der::uint(&self.r), &der::uint(&self.s)])
+    }
+
+    pub fn r(&self) -> &[u8; 32] {
+        &self.r
+    }
+
+    pub fn s(&self) -> &[u8; 32] {
+        &self.s
+    }
+
+    /// 解析 `SM2::sign` 输出的 `r h s` 十六进制字符串
+    pub fn from_hex(hex: &str) -> Result<Self, SM2Error> {
+        let (r, s) = hex.split_once('h').ok_or(SM2Error::InvalidSignature)?;
+        let r = parse_scalar_hex(r).ok_or(SM2Error::InvalidSignature)?;
+        let s = parse_scalar_hex(s).ok_or(SM2Error::InvalidSignature)?;
+        Self::from_scalars(&r, &s)
+    }
+
+    /// 编码为与 `SM2::sign` 相同的 `r h s` 格式
+    pub fn to_hex(&self) -> String {
+        format!(
+            "{}h{}",
+            BigInt256::from_be_bytes(&self.r).to_hex_lower(),
+            BigInt256::from_be_bytes(&self.s).to_hex_lower()
+        )
+    }
+
+    fn from_bigints(r: &BigInt256, s: &BigInt256) -> Self {
+        Signature { r: r.to_be_bytes(), s: s.to_be_bytes() }
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = SM2Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, SM2Error> {
+        Self::from_slice(bytes)
+    }
+}
+
+impl TryFrom<[u8; 64]> for Signature {
+    type Error = SM2Error;
+
+    fn try_from(bytes: [u8; 64]) -> Result<Self, SM2Error> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl From<Signature> for [u8; 64] {
+    fn from(sig: Signature) -> Self {
+        sig.to_bytes()
+    }
+}
+
+/// 签名私钥，附带对应的验签公钥与区分标识
+#[derive(Clone)]
+pub struct SigningKey {
+    d: BigInt256,
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    /// 由32字节大端私钥构造，使用默认ID；私钥必须在 [1, n-2] 内
+    pub fn from_bytes(secret: &[u8; 32]) -> Result<Self, SM2Error> {
+        let d = BigInt256::from_be_bytes(secret);
+        // 签名需要 (1 + d)^-1，d = n-1 不可用
+        let (n_minus_1, _) = SM2_N.sub(&BigInt256::ONE);
+        if d.is_zero() || d.compare(&n_minus_1) != core::cmp::Ordering::Less {
+            return Err(SM2Error::InvalidPrivateKey);
+        }
+        let point = SM2::get_public_key(&d);
+        Ok(SigningKey { d, verifying_key: VerifyingKey::from_point(point, DEFAULT_DISTID) })
+    }
+
+    /// 解析 `SM2::gen_key_pair` 输出的64位十六进制私钥
+    pub fn from_hex(hex: &str) -> Result<Self, SM2Error> {
+        let secret = parse_scalar_hex(hex).ok_or(SM2Error::InvalidPrivateKey)?;
+        Self::from_bytes(&secret)
+    }
+
+    /// 随机生成私钥，使用默认ID，系统随机源不可用时 panic
+    pub fn random() -> Self {
+        loop {
+            let secret = SM2::random_bigint().expect("system random number generator unavailable");
+            if let Ok(key) = Self::from_bytes(&secret.to_be_bytes()) {
+                return key;
+            }
+        }
+    }
+
+    /// 替换区分标识，签名时的 Z 值随之改变
+    pub fn with_distid(mut self, distid: &[u8]) -> Result<Self, SM2Error> {
+        self.verifying_key = self.verifying_key.with_distid(distid)?;
+        Ok(self)
+    }
+
+    /// 私钥的32字节大端编码
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.d.to_be_bytes()
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    pub fn distid(&self) -> &[u8] {
+        self.verifying_key.distid()
+    }
+
+    /// 签名：e = SM3(Z || M)，系统随机源不可用时 panic，需要处理该错误时使用 [`SigningKey::try_sign`]
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.try_sign(message).expect("system random number generator unavailable")
+    }
+
+    /// 签名，系统随机源不可用时返回 [`SM2Error::RandomUnavailable`]
+    pub fn try_sign(&self, message: &[u8]) -> Result<Signature, SM2Error> {
+        let e = SM2::message_digest(&self.verifying_key.z, message);
+        let (r, s) = SM2::sign_prehash(&self.d, &e)?;
+        Ok(Signature::from_bigints(&r, &s))
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 不输出私钥
+        f.debug_struct("SigningKey").field("verifying_key", &self.verifying_key).finish_non_exhaustive()
+    }
+}
+
+/// 验签公钥，Z 值在构造时计算一次
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    point: ECPoint,
+    distid: Vec<u8>,
+    z: [u8; 32],
+}
+
+impl VerifyingKey {
+    /// 解析未压缩点编码 04 || x || y，使用默认ID
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, SM2Error> {
+        if bytes.len() != 65 || bytes[0] != 0x04 {
+            return Err(SM2Error::InvalidPublicKey);
+        }
+        for coord in [&bytes[1..33], &bytes[33..]] {
+            if BigInt256::from_be_bytes(coord).compare(&SM2_P) != core::cmp::Ordering::Less {
+                return Err(SM2Error::InvalidPublicKey);
+            }
+        }
+        let point = ECPoint::from_encoded(bytes);
+        if !point.is_on_curve() {
+            return Err(SM2Error::InvalidPublicKey);
+        }
+        Ok(Self::from_point(point, DEFAULT_DISTID))
+    }
+
+    /// 解析 `SM2::gen_key_pair` 输出的十六进制公钥
+    pub fn from_hex(hex: &str) -> Result<Self, SM2Error> {
+        let bytes = hex_to_bytes(hex).map_err(|_| SM2Error::InvalidPublicKey)?;
+        Self::from_sec1_bytes(&bytes)
+    }
+
+    /// 替换区分标识
+    pub fn with_distid(self, distid: &[u8]) -> Result<Self, SM2Error> {
+        if distid.len() > MAX_DISTID_LEN {
+            return Err(SM2Error::InvalidDistid);
+        }
+        Ok(Self::from_point(self.point, distid))
+    }
+
+    /// 未压缩点编码 04 || x || y
+    pub fn to_sec1_bytes(&self) -> [u8; 65] {
+        let mut out = [0u8; 65];
+        out.copy_from_slice(&self.point.to_encoded());
+        out
+    }
+
+    pub fn point(&self) -> &ECPoint {
+        &self.point
+    }
+
+    pub fn distid(&self) -> &[u8] {
+        &self.distid
+    }
+
+    /// Z = SM3(ENTL || ID || a || b || Gx || Gy || x || y)
+    pub fn z(&self) -> &[u8; 32] {
+        &self.z
+    }
+
+    /// 验签
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let e = SM2::message_digest(&self.z, message);
+        let r = BigInt256::from_be_bytes(&signature.r);
+        let s = BigInt256::from_be_bytes(&signature.s);
+        SM2::verify_prehash(&self.point, &e, &r, &s)
+    }
+
+    // 调用方保证点有效、ID长度合法
+    pub(crate) fn from_point(point: ECPoint, distid: &[u8]) -> Self {
+        let z = SM2::user_sm3_z(distid, &point).try_into().unwrap();
+        VerifyingKey { point, distid: distid.to_vec(), z }
+    }
+}
+
+// 64位十六进制，允许省略前导零
+fn parse_scalar_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.is_empty() || hex.len() > 64 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(BigInt256::from_hex(hex).to_be_bytes())
+}
+
+#[cfg(feature = "signature")]
+mod signature_impl {
+    use signature::{Error, Keypair, SignatureEncoding, Signer, Verifier};
+
+    use super::{Signature, SigningKey, VerifyingKey};
+
+    impl SignatureEncoding for Signature {
+        type Repr = [u8; 64];
+    }
+
+    impl Signer<Signature> for SigningKey {
+        fn try_sign(&self, msg: &[u8]) -> Result<Signature, Error> {
+            SigningKey::try_sign(self, msg).map_err(|_| Error::new())
+        }
+    }
+
+    impl Verifier<Signature> for VerifyingKey {
+        fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), Error> {
+            if VerifyingKey::verify(self, msg, signature) { Ok(()) } else { Err(Error::new()) }
+        }
+    }
+
+    impl Keypair for SigningKey {
+        type VerifyingKey = VerifyingKey;
+
+        fn verifying_key(&self) -> VerifyingKey {
+            self.verifying_key.clone()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn roundtrip<S: Signer<Signature> + Keypair<VerifyingKey = V>, V: Verifier<Signature>>(key: &S) {
+            let sig = key.try_sign(b"message").unwrap();
+            let vk = key.verifying_key();
+            assert!(vk.verify(b"message", &sig).is_ok());
+            assert!(vk.verify(b"other", &sig).is_err());
+
+            let bytes = SignatureEncoding::to_bytes(&sig);
+            assert_eq!(Signature::try_from(&bytes[..]).unwrap(), sig);
+        }
+
+        #[test]
+        fn test_signature_traits() {
+            roundtrip(&SigningKey::random());
+            roundtrip(&SigningKey::random().with_distid(b"alice@example.com").unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // GM/T 0003.5 附录A 签名示例的私钥与公钥
+    const SECRET: &str = "3945208F7B2144B13F36E38AC6D39F95889393692860B51A42FB81EF4DF7C5B8";
+    const PUBLIC: &str = "0409F9DF311E5421A150DD7D161E4BC5C672179FAD1833FC076BB08FF356F35020\
+                          CCEA490CE26775A52DC6EA718CC1AA600AED05FBF35E084A6632F6072DA9AD13";
+
+    #[test]
+    fn test_keys() {
+        let sk = SigningKey::from_hex(SECRET).unwrap();
+        let vk = VerifyingKey::from_hex(PUBLIC).unwrap();
+        assert_eq!(sk.verifying_key(), &vk);
+        assert_eq!(sk.distid(), DEFAULT_DISTID);
+        assert_eq!(SigningKey::from_bytes(&sk.to_bytes()).unwrap().verifying_key(), &vk);
+        assert_eq!(VerifyingKey::from_sec1_bytes(&vk.to_sec1_bytes()).unwrap(), vk);
+
+        // 私钥范围 [1, n-2]
+        assert_eq!(SigningKey::from_bytes(&[0u8; 32]).err(), Some(SM2Error::InvalidPrivateKey));
+        let (n_minus_1, _) = SM2_N.sub(&BigInt256::ONE);
+        assert!(SigningKey::from_bytes(&n_minus_1.to_be_bytes()).is_err());
+        let (n_minus_2, _) = n_minus_1.sub(&BigInt256::ONE);
+        assert!(SigningKey::from_bytes(&n_minus_2.to_be_bytes()).is_ok());
+
+        // 不在曲线上、坐标越界或格式错误的公钥
+        let mut bad = vk.to_sec1_bytes();
+        bad[64] ^= 1;
+        assert_eq!(VerifyingKey::from_sec1_bytes(&bad).err(), Some(SM2Error::InvalidPublicKey));
+        assert!(VerifyingKey::from_sec1_bytes(&[0u8]).is_err());
+        let mut big = [0xFFu8; 65];
+        big[0] = 0x04;
+        assert!(VerifyingKey::from_sec1_bytes(&big).is_err());
+
+        assert_eq!(
+            SigningKey::random().with_distid(&vec![0u8; 8192]).err(),
+            Some(SM2Error::InvalidDistid)
+        );
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let sk = SigningKey::random();
+        let vk = sk.verifying_key();
+        let sig = sk.sign(b"message digest");
+        assert!(vk.verify(b"message digest", &sig));
+        assert!(!vk.verify(b"message digesT", &sig));
+        assert_eq!(Signature::from_bytes(&sig.to_bytes()).unwrap(), sig);
+
+        // ID 不同则 Z 不同，签名不能通过
+        let other = vk.clone().with_distid(b"ALICE123@YAHOO.COM").unwrap();
+        assert_ne!(other.z(), vk.z());
+        assert!(!other.verify(b"message digest", &sig));
+
+        let sk = sk.with_distid(b"ALICE123@YAHOO.COM").unwrap();
+        assert!(other.verify(b"message digest", &sk.sign(b"message digest")));
+    }
+
+    #[test]
+    fn test_interop_with_string_api() {
+        let sk = SigningKey::from_hex(SECRET).unwrap();
+        let id = "1234567812345678";
+
+        let legacy = SM2::sign(id, "message digest", SECRET).unwrap();
+        let sig = Signature::from_hex(&legacy).unwrap();
+        assert_eq!(sig.to_hex(), legacy);
+        assert!(sk.verifying_key().verify(b"message digest", &sig));
+
+        let sig = sk.sign(b"message digest");
+        assert!(SM2::verify(id, &sig.to_hex(), "message digest", PUBLIC));
+    }
+
+    #[test]
+    fn test_signature_range() {
+        let zero = [0u8; 32];
+        let one = BigInt256::ONE.to_be_bytes();
+        let n = SM2_N.to_be_bytes();
+        assert!(Signature::from_scalars(&one, &one).is_ok());
+        assert_eq!(Signature::from_scalars(&zero, &one).err(), Some(SM2Error::InvalidSignature));
+        assert!(Signature::from_scalars(&one, &n).is_err());
+        assert!(Signature::from_slice(&[1u8; 63]).is_err());
+        assert!(Signature::from_hex("1h").is_err());
+        assert!(Signature::from_hex("xyzh1").is_err());
+    }
+
+    #[test]
+    fn test_signature_der() {
+        let sig = SigningKey::random().sign(b"abc");
+        assert_eq!(Signature::from_der(&sig.to_der()).unwrap(), sig);
+
+        // 短整数去掉前导零，高位为1时补零
+        let mut r = [0u8; 32];
+        r[31] = 1;
+        let sig = Signature::from_scalars(&r, &[0x80; 32]).unwrap();
+        let der = sig.to_der();
+        assert_eq!(&der[..5], [0x30, 0x26, 0x02, 0x01, 0x01]);
+        assert_eq!(&der[5..8], [0x02, 0x21, 0x00]);
+        assert_eq!(Signature::from_der(&der).unwrap(), sig);
+
+        assert!(Signature::from_der(&der[..der.len() - 1]).is_err());
+        let long = der::sequence(&[&der::uint(&[1u8; 33]), &der::uint(&[1])]);
+        assert_eq!(Signature::from_der(&long).err(), Some(SM2Error::InvalidSignature));
+    }
+}
@@ -0,0 +1,92 @@
+//! SM2 公钥的 X.509 `SubjectPublicKeyInfo`（RFC 5280/5480）编码
+//!
+//! 算法标识与 PKCS#8 相同；解码得到的公钥使用默认ID。
+
+use crate::der::{self, Reader};
+use crate::pem;
+use crate::sm3::Sm3;
+
+use super::pkcs8::{algorithm_identifier, parse_algorithm_identifier};
+use super::signing::{SM2Error, VerifyingKey};
+
+const SPKI_LABEL: &str = "PUBLIC KEY";
+
+impl VerifyingKey {
+    /// 编码为 `SubjectPublicKeyInfo`，公钥为未压缩点
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        der::sequence(&[&algorithm_identifier(), &der::bit_string(&self.to_sec1_bytes())])
+    }
+
+    pub fn from_public_key_der(bytes: &[u8]) -> Result<Self, SM2Error> {
+        let mut outer = Reader::new(bytes);
+        let mut seq = outer.sequence()?;
+        outer.finish()?;
+        Self::parse_spki(&mut seq)
+    }
+
+    pub fn to_public_key_pem(&self) -> String {
+        pem::encode(SPKI_LABEL, &self.to_public_key_der())
+    }
+
+    pub fn from_public_key_pem(text: &str) -> Result<Self, SM2Error> {
+        let bytes = pem::decode(text, SPKI_LABEL).ok_or(SM2Error::InvalidEncoding)?;
+        Self::from_public_key_der(&bytes)
+    }
+
+    /// 公钥指纹：DER 编码的 `SubjectPublicKeyInfo` 的 SM3 摘要，可用于公钥固定
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut sm3 = Sm3::new();
+        sm3.update(&self.to_public_key_der());
+        sm3.finish();
+        *sm3.hash_bytes()
+    }
+
+    /// 解析 `SubjectPublicKeyInfo` 的内容，供证书解析复用
+    pub(crate) fn parse_spki(seq: &mut Reader) -> Result<Self, SM2Error> {
+        parse_algorithm_identifier(seq)?;
+        let point = seq.bit_string()?;
+        seq.finish()?;
+        Self::from_sec1_bytes(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm2::SigningKey;
+
+    // 由 OpenSSL 3 `pkey -pubout` 导出，对应 pkcs8 测试中的私钥
+    const OPENSSL_SPKI: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoEcz1UBgi0DQgAE82Sl42oc0xSh+FH5dCHZH1y+DZaB
+RWpt+i0sOaeRKb8k4y4qqj7mfOYPMzi8cc6c9twt1X+Z3edwZRu8OUJmog==
+-----END PUBLIC KEY-----
+";
+    const SECRET: &str = "69219FD40E10E281F79F30A3AE313B59107C71A81173EB96EDD4A0CD2360E97B";
+    // `pkey -pubin -outform DER | dgst -sm3`
+    const FINGERPRINT: &str = "cc49794ac15c567a42b54801491dbae29df526c1829bf5e15d910986bfb401d8";
+
+    #[test]
+    fn test_openssl_spki() {
+        let key = SigningKey::from_hex(SECRET).unwrap();
+        let public = VerifyingKey::from_public_key_pem(OPENSSL_SPKI).unwrap();
+        assert_eq!(&public, key.verifying_key());
+        assert_eq!(public.to_public_key_pem(), OPENSSL_SPKI);
+
+        let fingerprint: String = public.fingerprint().iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(fingerprint, FINGERPRINT);
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        let key = SigningKey::random();
+        let der = key.verifying_key().to_public_key_der();
+        assert_eq!(VerifyingKey::from_public_key_der(&der).unwrap(), *key.verifying_key());
+        assert!(VerifyingKey::from_public_key_der(&der[..der.len() - 1]).is_err());
+        assert!(VerifyingKey::from_public_key_pem(&key.to_pkcs8_pem()).is_err());
+
+        // 不在曲线上的点
+        let mut bad = der.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        assert_eq!(VerifyingKey::from_public_key_der(&bad).err(), Some(SM2Error::InvalidPublicKey));
+    }
+}
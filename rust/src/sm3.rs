@@ -1,12 +1,33 @@
 // SM3 实现（无第三方依赖），接口尽量贴近 Java/Swift/JS 版本
 
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+
+pub mod merkle;
+mod multi;
+pub mod stream;
+
+pub use multi::sm3_many;
+pub use stream::{HashingReader, sm3_file, sm3_reader};
+
+#[derive(Clone)]
 pub struct Sm3 {
     v: [u32; 8],
     buff: [u8; 64],
     buff_len: usize, // 已写入字节数
     data_bits_len: u64,
     hash_bytes: [u8; 32],
-    hash_value_hex: String,
+    // 十六进制结果在首次读取时才生成
+    hash_value_hex: OnceLock<String>,
+    // Java 兼容模式：长度字段只保留低32位
+    legacy_length: bool,
+}
+
+impl Default for Sm3 {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Sm3 {
@@ -21,6 +42,18 @@ impl Sm3 {
         0xB0FB_0E4E,
     ];
 
+    /// 预计算的 T_j <<< (j mod 32)
+    const T_ROTATED: [u32; 64] = {
+        let mut t = [0u32; 64];
+        let mut j = 0;
+        while j < 64 {
+            let base = if j < 16 { 0x79CC_4519u32 } else { 0x7A87_9D8Au32 };
+            t[j] = base.rotate_left((j % 32) as u32);
+            j += 1;
+        }
+        t
+    };
+
     pub fn new() -> Self {
         Sm3 {
             v: Self::IV,
@@ -28,10 +61,20 @@ impl Sm3 {
             buff_len: 0,
             data_bits_len: 0,
             hash_bytes: [0u8; 32],
-            hash_value_hex: String::new(),
+            hash_value_hex: OnceLock::new(),
+            legacy_length: false,
         }
     }
 
+    /// 旧版兼容模式：填充时长度字段只保留消息比特长度的低32位，与旧 Java 实现逐字节一致
+    ///
+    /// 超过 2^32 比特（512 MiB）的消息结果不符合 GB/T 32905，仅用于校验旧数据
+    pub fn new_legacy() -> Self {
+        let mut sm3 = Self::new();
+        sm3.legacy_length = true;
+        sm3
+    }
+
     #[inline]
     fn reset(&mut self) {
         self.v = Self::IV;
@@ -43,20 +86,39 @@ impl Sm3 {
     pub fn update_byte(&mut self, b: u8) -> &mut Self {
         self.buff[self.buff_len] = b;
         self.buff_len += 1;
-        self.data_bits_len += 8;
+        self.data_bits_len = self.data_bits_len.wrapping_add(8);
         if self.buff_len == 64 {
-            let block = self.buff;
-            self.process_block(&block);
+            Self::compress(&mut self.v, &self.buff);
             self.buff_len = 0;
         }
         self
     }
 
-    /// 更新字节数组
+    /// 更新字节数组，完整分组直接从输入切片压缩
     pub fn update(&mut self, data: &[u8]) -> &mut Self {
-        for &b in data {
-            self.update_byte(b);
+        let mut data = data;
+        self.data_bits_len = self.data_bits_len.wrapping_add((data.len() as u64) << 3);
+
+        // 先补齐缓冲区中的残留分组
+        if self.buff_len > 0 {
+            let n = (64 - self.buff_len).min(data.len());
+            self.buff[self.buff_len..self.buff_len + n].copy_from_slice(&data[..n]);
+            self.buff_len += n;
+            data = &data[n..];
+            if self.buff_len < 64 {
+                return self;
+            }
+            Self::compress(&mut self.v, &self.buff);
+            self.buff_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            Self::compress(&mut self.v, block);
         }
+        let rest = blocks.remainder();
+        self.buff[..rest.len()].copy_from_slice(rest);
+        self.buff_len = rest.len();
         self
     }
 
@@ -65,49 +127,62 @@ impl Sm3 {
         self.update(s.as_bytes())
     }
 
+    /// 结束计算，结果通过 `hash_bytes`/`hash_hex_upper` 读取，之后上下文重置可复用
     pub fn finish(&mut self) -> &mut Self {
-        // 完全按 Java 版本的填充逻辑
-        let end = &self.buff[..self.buff_len];
-        let block_len_bits = (self.buff_len as i32) * 8;
-        let data_len_bits = (self.data_bits_len & 0xFFFF_FFFF) as i32;
-
-        // 1
-        let one: u8 = 0x80;
-        // 需填0长度（bit）
-        let fill_zero_len_bits = (512 - (block_len_bits + 65) % 512) - 7;
-        // 总长度 bit
-        let all_len_bits = fill_zero_len_bits + block_len_bits + 65 + 7;
-        // 总长度 byte
-        let all_byte_len = all_len_bits / 8;
-
-        let mut buff = vec![0u8; all_byte_len as usize];
-        for i in 0..all_byte_len {
-            let idx = i as usize;
-            if idx < end.len() {
-                buff[idx] = end[idx];
-            } else if idx == end.len() {
-                buff[idx] = one;
-            } else if i > all_byte_len - 5 {
-                // 最后四字节填充全部数据的总长度（只保留32bit）
-                let shift = (all_byte_len - i - 1) * 8;
-                let val = (data_len_bits >> shift) & 0xFF;
-                buff[idx] = val as u8;
-            } else {
-                buff[idx] = 0;
-            }
+        self.hash_bytes = self.finalize();
+        self.hash_value_hex = OnceLock::new();
+        self.reset();
+        self
+    }
+
+    /// 计算当前已输入数据的摘要，不修改上下文，之后可继续 `update`
+    pub fn finalize(&self) -> [u8; 32] {
+        // 填充：消息 || 1 || 0...0 || 64位消息比特长度（大端）
+        let data_bits_len = if self.legacy_length {
+            self.data_bits_len & 0xFFFF_FFFF
+        } else {
+            self.data_bits_len
+        };
+
+        let mut buff = [0u8; 128];
+        buff[..self.buff_len].copy_from_slice(&self.buff[..self.buff_len]);
+        buff[self.buff_len] = 0x80;
+        // 剩余空间不足8字节长度字段时需多填充一个分组
+        let all_byte_len = if self.buff_len < 56 { 64 } else { 128 };
+        buff[all_byte_len - 8..all_byte_len].copy_from_slice(&data_bits_len.to_be_bytes());
+
+        // 在状态副本上处理填充后的块
+        let mut v = self.v;
+        for block in buff[..all_byte_len].chunks(64) {
+            Self::compress(&mut v, block);
         }
 
-        // 处理填充后的块
-        for i in 0..(all_len_bits / 512) {
-            let start = (i * 512 / 8) as usize;
-            let end = ((i + 1) * 512 / 8) as usize;
-            let block = &buff[start..end];
-            self.process_block(block);
+        let mut out = [0u8; 32];
+        for (i, w) in v.iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&w.to_be_bytes());
         }
+        out
+    }
 
-        self.generate_hash_string();
-        self.reset();
-        self
+    /// 导出中间状态，用于断点续算
+    pub fn export_state(&self) -> Sm3State {
+        Sm3State {
+            chaining: self.v,
+            buffer: self.buff[..self.buff_len].to_vec(),
+            bit_len: self.data_bits_len,
+            legacy_length: self.legacy_length,
+        }
+    }
+
+    /// 从导出的中间状态恢复上下文
+    pub fn from_state(state: &Sm3State) -> Result<Self, Sm3StateError> {
+        state.check()?;
+        let mut sm3 = if state.legacy_length { Self::new_legacy() } else { Self::new() };
+        sm3.v = state.chaining;
+        sm3.buff[..state.buffer.len()].copy_from_slice(&state.buffer);
+        sm3.buff_len = state.buffer.len();
+        sm3.data_bits_len = state.bit_len;
+        Ok(sm3)
     }
 
     pub fn hash_bytes(&self) -> &[u8; 32] {
@@ -115,99 +190,85 @@ impl Sm3 {
     }
 
     pub fn hash_hex_upper(&self) -> &str {
-        &self.hash_value_hex
-    }
-
-    fn generate_hash_string(&mut self) {
-        let mut out = [0u8; 32];
-        let mut off = 0;
-        for &v in &self.v {
-            out[off] = (v >> 24) as u8;
-            out[off + 1] = (v >> 16) as u8;
-            out[off + 2] = (v >> 8) as u8;
-            out[off + 3] = v as u8;
-            off += 4;
-        }
-        self.hash_bytes = out;
-        let mut s = String::with_capacity(64);
-        for b in &out {
-            s.push_str(&format!("{:02X}", b));
-        }
-        self.hash_value_hex = s;
+        self.hash_value_hex.get_or_init(|| {
+            const HEX: &[u8; 16] = b"0123456789ABCDEF";
+            let mut s = String::with_capacity(64);
+            for &b in &self.hash_bytes {
+                s.push(HEX[(b >> 4) as usize] as char);
+                s.push(HEX[(b & 0x0F) as usize] as char);
+            }
+            s
+        })
     }
 
-    fn process_block(&mut self, block: &[u8]) {
+    // 压缩函数 CF，64轮按4轮一组展开，通过轮换变量角色省去每轮的状态字搬移
+    fn compress(v: &mut [u32; 8], block: &[u8]) {
         debug_assert_eq!(block.len(), 64);
         // 消息扩展
         let mut w = [0u32; 68];
-        let mut offset = 0;
         for j in 0..16 {
-            w[j] = ((block[offset] as u32) << 24) |
-                   ((block[offset + 1] as u32) << 16) |
-                   ((block[offset + 2] as u32) << 8) |
-                   (block[offset + 3] as u32);
-            offset += 4;
+            w[j] = u32::from_be_bytes([block[j * 4], block[j * 4 + 1], block[j * 4 + 2], block[j * 4 + 3]]);
         }
         for j in 16..68 {
-            let wj3 = w[j - 3];
-            let r15 = wj3.rotate_left(15);
-            let wj13 = w[j - 13];
-            let r7 = wj13.rotate_left(7);
-            w[j] = Self::p1(w[j - 16] ^ w[j - 9] ^ r15) ^ r7 ^ w[j - 6];
-        }
-        let mut w2 = [0u32; 64];
-        for j in 0..64 {
-            w2[j] = w[j] ^ w[j + 4];
+            w[j] = Self::p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+                ^ w[j - 13].rotate_left(7)
+                ^ w[j - 6];
         }
 
-        // 压缩函数
-        let mut a = self.v[0];
-        let mut b = self.v[1];
-        let mut c = self.v[2];
-        let mut d = self.v[3];
-        let mut e = self.v[4];
-        let mut f = self.v[5];
-        let mut g = self.v[6];
-        let mut h = self.v[7];
-
-        for j in 0..64 {
-            let a12 = a.rotate_left(12);
-            let t_j = if j < 16 {
-                0x79CC_4519u32.rotate_left(j as u32)
-            } else {
-                0x7A87_9D8Au32.rotate_left((j % 32) as u32)
-            };
-            let s_s = a12.wrapping_add(e).wrapping_add(t_j);
-            let ss1 = s_s.rotate_left(7);
-            let ss2 = ss1 ^ a12;
-            let tt1 = if j < 16 {
-                (a ^ b ^ c).wrapping_add(d).wrapping_add(ss2).wrapping_add(w2[j])
-            } else {
-                Self::ff1(a, b, c).wrapping_add(d).wrapping_add(ss2).wrapping_add(w2[j])
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+
+        // 单轮：结果写入 d/h，b/f 原地循环移位，下一轮变量角色整体右移一位
+        macro_rules! round {
+            ($a:ident, $b:ident, $c:ident, $d:ident, $e:ident, $f:ident, $g:ident, $h:ident, $j:expr, $ff:expr, $gg:expr) => {
+                let a12 = $a.rotate_left(12);
+                let ss1 = a12.wrapping_add($e).wrapping_add(Self::T_ROTATED[$j]).rotate_left(7);
+                let ss2 = ss1 ^ a12;
+                let tt1 = $ff($a, $b, $c).wrapping_add($d).wrapping_add(ss2).wrapping_add(w[$j] ^ w[$j + 4]);
+                let tt2 = $gg($e, $f, $g).wrapping_add($h).wrapping_add(ss1).wrapping_add(w[$j]);
+                $b = $b.rotate_left(9);
+                $f = $f.rotate_left(19);
+                $d = tt1;
+                $h = Self::p0(tt2);
             };
-            let tt2 = if j < 16 {
-                (e ^ f ^ g).wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j])
-            } else {
-                Self::gg1(e, f, g).wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j])
+        }
+
+        macro_rules! four_rounds {
+            ($j:expr, $ff:expr, $gg:expr) => {
+                round!(a, b, c, d, e, f, g, h, $j, $ff, $gg);
+                round!(d, a, b, c, h, e, f, g, $j + 1, $ff, $gg);
+                round!(c, d, a, b, g, h, e, f, $j + 2, $ff, $gg);
+                round!(b, c, d, a, f, g, h, e, $j + 3, $ff, $gg);
             };
-            d = c;
-            c = b.rotate_left(9);
-            b = a;
-            a = tt1;
-            h = g;
-            g = f.rotate_left(19);
-            f = e;
-            e = Self::p0(tt2);
         }
 
-        self.v[0] ^= a;
-        self.v[1] ^= b;
-        self.v[2] ^= c;
-        self.v[3] ^= d;
-        self.v[4] ^= e;
-        self.v[5] ^= f;
-        self.v[6] ^= g;
-        self.v[7] ^= h;
+        let mut j = 0;
+        while j < 16 {
+            four_rounds!(j, Self::ff0, Self::gg0);
+            j += 4;
+        }
+        while j < 64 {
+            four_rounds!(j, Self::ff1, Self::gg1);
+            j += 4;
+        }
+
+        v[0] ^= a;
+        v[1] ^= b;
+        v[2] ^= c;
+        v[3] ^= d;
+        v[4] ^= e;
+        v[5] ^= f;
+        v[6] ^= g;
+        v[7] ^= h;
+    }
+
+    #[inline]
+    fn ff0(x: u32, y: u32, z: u32) -> u32 {
+        x ^ y ^ z
+    }
+
+    #[inline]
+    fn gg0(x: u32, y: u32, z: u32) -> u32 {
+        x ^ y ^ z
     }
 
     #[inline]
@@ -231,9 +292,164 @@ impl Sm3 {
     }
 }
 
+/// SM3 中间状态：链接变量、未满一个分组的缓冲数据与已处理的消息比特长度
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sm3State {
+    pub chaining: [u32; 8],
+    pub buffer: Vec<u8>,
+    pub bit_len: u64,
+    pub legacy_length: bool,
+}
+
+/// 中间状态编码的魔数与版本
+const STATE_MAGIC: [u8; 4] = *b"SM3\x01";
+/// 编码固定部分：魔数(4) || 标志(1) || 链接变量(32) || 比特长度(8)，其后为缓冲数据
+const STATE_HEADER_LEN: usize = 45;
+
+/// 中间状态导入错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sm3StateError {
+    InvalidEncoding,
+    InconsistentLength,
+}
+
+impl fmt::Display for Sm3StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sm3StateError::InvalidEncoding => write!(f, "Invalid SM3 state encoding"),
+            Sm3StateError::InconsistentLength => write!(f, "Buffered data does not match message length"),
+        }
+    }
+}
+
+impl Error for Sm3StateError {}
+
+impl Sm3State {
+    /// 序列化为字节串，长度为 45 + 缓冲数据长度
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(STATE_HEADER_LEN + self.buffer.len());
+        out.extend_from_slice(&STATE_MAGIC);
+        out.push(self.legacy_length as u8);
+        for w in &self.chaining {
+            out.extend_from_slice(&w.to_be_bytes());
+        }
+        out.extend_from_slice(&self.bit_len.to_be_bytes());
+        out.extend_from_slice(&self.buffer);
+        out
+    }
+
+    /// 从 `to_bytes` 的结果解析
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Sm3StateError> {
+        if bytes.len() < STATE_HEADER_LEN || bytes[..4] != STATE_MAGIC || bytes[4] > 1 {
+            return Err(Sm3StateError::InvalidEncoding);
+        }
+        let mut chaining = [0u32; 8];
+        for (i, w) in chaining.iter_mut().enumerate() {
+            let off = 5 + i * 4;
+            *w = u32::from_be_bytes([bytes[off], bytes[off + 1], bytes[off + 2], bytes[off + 3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&bytes[37..45]);
+        let state = Sm3State {
+            chaining,
+            buffer: bytes[STATE_HEADER_LEN..].to_vec(),
+            bit_len: u64::from_be_bytes(len),
+            legacy_length: bytes[4] == 1,
+        };
+        state.check()?;
+        Ok(state)
+    }
+
+    // 缓冲数据长度必须等于消息字节数模64
+    fn check(&self) -> Result<(), Sm3StateError> {
+        if !self.bit_len.is_multiple_of(8) || self.buffer.len() as u64 != (self.bit_len / 8) % 64 {
+            return Err(Sm3StateError::InconsistentLength);
+        }
+        Ok(())
+    }
+}
+
+/// RustCrypto `digest` 0.10 trait 实现，可用于 `hmac::SimpleHmac<Sm3>` 等泛型代码
+#[cfg(feature = "digest")]
+mod digest_impl {
+    use digest::consts::{U32, U64};
+    use digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+    use digest::core_api::BlockSizeUser;
+
+    use super::Sm3;
+
+    impl HashMarker for Sm3 {}
+
+    impl BlockSizeUser for Sm3 {
+        type BlockSize = U64;
+    }
+
+    impl OutputSizeUser for Sm3 {
+        type OutputSize = U32;
+    }
+
+    impl Update for Sm3 {
+        fn update(&mut self, data: &[u8]) {
+            Sm3::update(self, data);
+        }
+    }
+
+    impl FixedOutput for Sm3 {
+        fn finalize_into(self, out: &mut Output<Self>) {
+            out.copy_from_slice(&Sm3::finalize(&self));
+        }
+    }
+
+    impl Reset for Sm3 {
+        fn reset(&mut self) {
+            Sm3::reset(self);
+        }
+    }
+
+    impl FixedOutputReset for Sm3 {
+        fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+            out.copy_from_slice(&Sm3::finalize(self));
+            Sm3::reset(self);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use digest::Digest;
+
+        use super::Sm3;
+
+        fn hash<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut d = D::new();
+            for p in parts {
+                Digest::update(&mut d, p);
+            }
+            d.finalize().to_vec()
+        }
+
+        #[test]
+        fn test_digest_traits() {
+            let mut sm3 = Sm3::new();
+            sm3.update(b"abc");
+            // 引入 Digest 后按值调用的 `finalize` 解析为 trait 方法，这里显式调用固有方法
+            let expected = Sm3::finalize(&sm3);
+
+            assert_eq!(<Sm3 as Digest>::digest(b"abc").as_slice(), expected);
+            assert_eq!(hash::<Sm3>(&[b"a", b"", b"bc"]), expected);
+            assert_eq!(<Sm3 as Digest>::output_size(), 32);
+
+            let mut d = <Sm3 as Digest>::new();
+            Digest::update(&mut d, b"abc");
+            assert_eq!(d.finalize_reset().as_slice(), expected);
+            Digest::update(&mut d, b"abc");
+            assert_eq!(Digest::finalize(d).as_slice(), expected);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Sm3;
+    use super::*;
 
     fn sm3_hex(input: &str) -> String {
         let mut sm3 = Sm3::new();
@@ -258,4 +474,155 @@ mod tests {
             "1AB21D8355CFA17F8E61194831E81A8F22BEC8C728FEFB747ED035EB5082AA2B"
         );
     }
+
+    #[test]
+    fn test_sm3_update_splits() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 3) as u8).collect();
+        let mut one = Sm3::new();
+        one.update(&data).finish();
+
+        for step in [1, 3, 63, 64, 65, 200] {
+            let mut parts = Sm3::new();
+            for chunk in data.chunks(step) {
+                parts.update(chunk);
+            }
+            parts.finish();
+            assert_eq!(parts.hash_bytes(), one.hash_bytes(), "step={}", step);
+        }
+
+        let mut bytes = Sm3::new();
+        for &b in &data[..500] {
+            bytes.update_byte(b);
+        }
+        bytes.update(&data[500..]).finish();
+        assert_eq!(bytes.hash_bytes(), one.hash_bytes());
+        assert_eq!(bytes.hash_hex_upper(), one.hash_hex_upper());
+    }
+
+    #[test]
+    fn test_sm3_padding_boundaries() {
+        // 期望值由 OpenSSL 3 `dgst -sm3` 计算
+        let cases = [
+            (55, "288337eef51eec62e7544d7270424c8dbe656254c99852870a73b2453a6a7fb1"),
+            (56, "ba00ebedaab54065a5fd4f9f56326016203166bcee3eed44ea868d59d67aa3c8"),
+            (64, "616ec433c359e7c2b19f360e2b8f2a1b6e9ed76b8dc1a7d207b31a5341c611e9"),
+        ];
+        for (len, expected) in cases {
+            let mut sm3 = Sm3::new();
+            sm3.update(&vec![b'a'; len]).finish();
+            let hex: String = sm3.hash_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(hex, expected, "len={}", len);
+        }
+    }
+
+    #[test]
+    fn test_sm3_length_field() {
+        // 伪造已处理的长度，只比较长度字段对结果的影响
+        let digest = |legacy: bool, bits: u64| {
+            let mut sm3 = if legacy { Sm3::new_legacy() } else { Sm3::new() };
+            sm3.data_bits_len = bits;
+            sm3.finish();
+            *sm3.hash_bytes()
+        };
+
+        // 2^32 比特以内两种模式一致
+        assert_eq!(digest(false, 0xFFFF_FFF8), digest(true, 0xFFFF_FFF8));
+        // 超过后标准模式编码完整64位长度
+        assert_ne!(digest(false, 1 << 32), digest(true, 1 << 32));
+        assert_eq!(digest(true, 1 << 32), digest(true, 0));
+        assert_eq!(digest(false, 0), digest(true, 0));
+    }
+
+    fn sm3_zeros(len: u64, legacy: bool) -> String {
+        let mut sm3 = if legacy { Sm3::new_legacy() } else { Sm3::new() };
+        let chunk = vec![0u8; 1 << 20];
+        let mut remaining = len;
+        while remaining > 0 {
+            let n = remaining.min(chunk.len() as u64) as usize;
+            sm3.update(&chunk[..n]);
+            remaining -= n as u64;
+        }
+        sm3.finish();
+        sm3.hash_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // 大数据量测试耗时较长，使用 `cargo test --release -- --ignored` 运行
+    #[test]
+    #[ignore]
+    fn test_sm3_over_512_mib() {
+        // 512 MiB + 1 字节，长度首次超过 2^32 比特
+        let expected = "1860c1d3654409dd1bbc7aea48889ae732d3aa767f282add9cea59a059fc6d1f";
+        assert_eq!(sm3_zeros((512 << 20) + 1, false), expected);
+        assert_ne!(sm3_zeros((512 << 20) + 1, true), expected);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_sm3_5_gib() {
+        assert_eq!(
+            sm3_zeros(5 << 30, false),
+            "aae718f40d8d6b798e77bf732ff638d906ff62ae53eaed47b9e1ae1f692e030e"
+        );
+    }
+
+    #[test]
+    fn test_sm3_finalize_non_destructive() {
+        let mut sm3 = Sm3::new();
+        sm3.update(b"ab");
+        let prefix = sm3.clone();
+        assert_eq!(sm3.finalize(), sm3.finalize());
+        sm3.update(b"c");
+        let abc = sm3.finalize();
+        sm3.finish();
+        assert_eq!(&abc, sm3.hash_bytes());
+
+        // 共享前缀分叉
+        let mut fork = prefix.clone();
+        fork.update(b"c");
+        assert_eq!(fork.finalize(), abc);
+        let mut other = prefix;
+        other.update(b"d");
+        assert_ne!(other.finalize(), abc);
+    }
+
+    #[test]
+    fn test_sm3_state_resume() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 13 + 5) as u8).collect();
+        let mut expected = Sm3::new();
+        expected.update(&data).finish();
+
+        for split in [0, 1, 63, 64, 100, 999] {
+            let mut sm3 = Sm3::new();
+            sm3.update(&data[..split]);
+            let bytes = sm3.export_state().to_bytes();
+            assert_eq!(bytes.len(), 45 + split % 64);
+
+            let mut resumed = Sm3::from_state(&Sm3State::from_bytes(&bytes).unwrap()).unwrap();
+            resumed.update(&data[split..]).finish();
+            assert_eq!(resumed.hash_bytes(), expected.hash_bytes(), "split={}", split);
+        }
+
+        // 兼容模式标志一并保存
+        let mut legacy = Sm3::new_legacy();
+        legacy.update(b"abc");
+        let state = Sm3State::from_bytes(&legacy.export_state().to_bytes()).unwrap();
+        assert!(state.legacy_length);
+    }
+
+    #[test]
+    fn test_sm3_state_invalid() {
+        let mut sm3 = Sm3::new();
+        sm3.update(b"abc");
+        let bytes = sm3.export_state().to_bytes();
+
+        assert_eq!(Sm3State::from_bytes(&bytes[..40]), Err(Sm3StateError::InvalidEncoding));
+        let mut bad = bytes.clone();
+        bad[0] ^= 1;
+        assert_eq!(Sm3State::from_bytes(&bad), Err(Sm3StateError::InvalidEncoding));
+        assert_eq!(Sm3State::from_bytes(&bytes[..47]), Err(Sm3StateError::InconsistentLength));
+
+        let mut state = sm3.export_state();
+        state.bit_len += 8;
+        assert_eq!(Sm3::from_state(&state).err(), Some(Sm3StateError::InconsistentLength));
+    }
 }
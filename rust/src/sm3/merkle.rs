@@ -0,0 +1,295 @@
+//! 基于SM3的Merkle树（结构同 RFC 6962）
+//!
+//! - 叶子：SM3(0x00 || 分块)，内部节点：SM3(0x01 || 左 || 右)，前缀区分两类哈希，防止以节点冒充叶子
+//! - 每层节点数为奇数时，最后一个节点原样提升到上一层
+//! - 无数据时根为 SM3("")
+//!
+//! `MerkleTree` 保存全部节点，可生成包含证明；`MerkleHasher` 流式计算，只保留 O(log n) 个子树根。
+
+use std::io::{self, Write};
+
+use super::Sm3;
+
+/// 默认分块大小
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// 叶子哈希
+pub fn leaf_hash(chunk: &[u8]) -> [u8; 32] {
+    let mut sm3 = Sm3::new();
+    sm3.update(&[LEAF_PREFIX]).update(chunk);
+    sm3.finalize()
+}
+
+/// 内部节点哈希
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut sm3 = Sm3::new();
+    sm3.update(&[NODE_PREFIX]).update(left).update(right);
+    sm3.finalize()
+}
+
+fn empty_root() -> [u8; 32] {
+    Sm3::new().finalize()
+}
+
+/// 完整的Merkle树
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    // levels[0] 为叶子层，最后一层只有根
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// 按 `chunk_size` 分块建树，最后一块可以不满；`chunk_size` 必须大于0
+    pub fn from_data(data: &[u8], chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be non-zero");
+        Self::from_leaf_hashes(data.chunks(chunk_size).map(leaf_hash).collect())
+    }
+
+    /// 由已计算好的叶子哈希建树
+    pub fn from_leaf_hashes(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let prev = levels.last().unwrap();
+            let next = prev
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { node_hash(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap().first().copied().unwrap_or_else(empty_root)
+    }
+
+    /// 第 `index` 个叶子的包含证明，越界返回 None
+    pub fn proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = i ^ 1;
+            if sibling < level.len() {
+                path.push(level[sibling]);
+            }
+            i >>= 1;
+        }
+        Some(InclusionProof { index, leaf_count: self.leaf_count(), path })
+    }
+}
+
+/// 包含证明：从叶子到根依次经过的兄弟节点
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub path: Vec<[u8; 32]>,
+}
+
+impl InclusionProof {
+    /// 校验分块属于根为 `root` 的树
+    pub fn verify(&self, chunk: &[u8], root: &[u8; 32]) -> bool {
+        self.verify_leaf_hash(&leaf_hash(chunk), root)
+    }
+
+    /// 校验叶子哈希属于根为 `root` 的树
+    pub fn verify_leaf_hash(&self, leaf: &[u8; 32], root: &[u8; 32]) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let mut hash = *leaf;
+        let mut path = self.path.iter();
+        let (mut i, mut width) = (self.index, self.leaf_count);
+        while width > 1 {
+            if i & 1 == 1 {
+                match path.next() {
+                    Some(left) => hash = node_hash(left, &hash),
+                    None => return false,
+                }
+            } else if i + 1 < width {
+                match path.next() {
+                    Some(right) => hash = node_hash(&hash, right),
+                    None => return false,
+                }
+            }
+            i >>= 1;
+            width = width.div_ceil(2);
+        }
+        path.next().is_none() && hash == *root
+    }
+}
+
+/// 流式计算Merkle根，内存占用与数据量无关
+#[derive(Clone)]
+pub struct MerkleHasher {
+    chunk_size: usize,
+    buff: Vec<u8>,
+    // 已完成的满二叉子树根及其叶子数，叶子数自底向上严格递减
+    stack: Vec<([u8; 32], u64)>,
+}
+
+impl Default for MerkleHasher {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHUNK_SIZE)
+    }
+}
+
+impl MerkleHasher {
+    /// `chunk_size` 必须大于0
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be non-zero");
+        MerkleHasher { chunk_size, buff: Vec::with_capacity(chunk_size), stack: Vec::new() }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) -> &mut Self {
+        while !data.is_empty() {
+            // 缓冲区满时才作为叶子处理，保证最后一块留到 finalize
+            if self.buff.len() == self.chunk_size {
+                let leaf = leaf_hash(&self.buff);
+                self.buff.clear();
+                self.push_leaf(leaf);
+            }
+            let n = (self.chunk_size - self.buff.len()).min(data.len());
+            self.buff.extend_from_slice(&data[..n]);
+            data = &data[n..];
+        }
+        self
+    }
+
+    // 相同大小的子树合并
+    fn push_leaf(&mut self, leaf: [u8; 32]) {
+        let mut node = (leaf, 1u64);
+        while let Some(&(left, size)) = self.stack.last() {
+            if size != node.1 {
+                break;
+            }
+            self.stack.pop();
+            node = (node_hash(&left, &node.0), size * 2);
+        }
+        self.stack.push(node);
+    }
+
+    /// 计算根，结果与 `MerkleTree::from_data(全部数据, chunk_size).root()` 相同
+    pub fn finalize(mut self) -> [u8; 32] {
+        if !self.buff.is_empty() {
+            let leaf = leaf_hash(&self.buff);
+            self.push_leaf(leaf);
+        }
+        // 自右向左折叠剩余的子树根
+        let mut iter = self.stack.iter().rev();
+        let Some(&(mut root, _)) = iter.next() else {
+            return empty_root();
+        };
+        for (left, _) in iter {
+            root = node_hash(left, &root);
+        }
+        root
+    }
+}
+
+impl Write for MerkleHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn test_small_trees() {
+        assert_eq!(MerkleTree::from_data(b"", 4).root(), empty_root());
+        assert_eq!(MerkleTree::from_data(b"abc", 4).root(), leaf_hash(b"abc"));
+
+        // 3个叶子：root = H(H(l0, l1), l2)
+        let tree = MerkleTree::from_data(b"aaaabbbbcc", 4);
+        let (l0, l1, l2) = (leaf_hash(b"aaaa"), leaf_hash(b"bbbb"), leaf_hash(b"cc"));
+        assert_eq!(tree.leaf_count(), 3);
+        assert_eq!(tree.root(), node_hash(&node_hash(&l0, &l1), &l2));
+
+        // 叶子与节点哈希带前缀，与直接SM3不同
+        let mut sm3 = Sm3::new();
+        sm3.update(b"abc");
+        assert_ne!(leaf_hash(b"abc"), sm3.finalize());
+    }
+
+    #[test]
+    fn test_streaming_matches_tree() {
+        for (len, chunk) in [(0, 16), (1, 16), (16, 16), (17, 16), (1000, 16), (5000, 7), (4096, 1024)] {
+            let data = sample(len);
+            let expected = MerkleTree::from_data(&data, chunk).root();
+            for step in [1, 5, 16, 999] {
+                let mut hasher = MerkleHasher::new(chunk);
+                for part in data.chunks(step) {
+                    hasher.update(part);
+                }
+                assert_eq!(hasher.finalize(), expected, "len={} chunk={} step={}", len, chunk, step);
+            }
+        }
+
+        let data = sample(3000);
+        let mut hasher = MerkleHasher::new(100);
+        io::copy(&mut &data[..], &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), MerkleTree::from_data(&data, 100).root());
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        for leaves in [1, 2, 3, 5, 8, 13, 33] {
+            let data = sample(leaves * 10 - 3);
+            let tree = MerkleTree::from_data(&data, 10);
+            let root = tree.root();
+            for (i, chunk) in data.chunks(10).enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert!(proof.verify(chunk, &root), "leaves={} i={}", leaves, i);
+                assert!(!proof.verify(b"tampered", &root));
+            }
+            assert!(tree.proof(leaves).is_none());
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_mismatch() {
+        let data = sample(100);
+        let tree = MerkleTree::from_data(&data, 10);
+        let root = tree.root();
+        let proof = tree.proof(4).unwrap();
+        let chunk = &data[40..50];
+
+        let mut wrong_index = proof.clone();
+        wrong_index.index = 5;
+        assert!(!wrong_index.verify(chunk, &root));
+
+        let mut short = proof.clone();
+        short.path.pop();
+        assert!(!short.verify(chunk, &root));
+
+        let mut long = proof.clone();
+        long.path.push([0u8; 32]);
+        assert!(!long.verify(chunk, &root));
+
+        let mut out_of_range = proof;
+        out_of_range.index = 10;
+        assert!(!out_of_range.verify(chunk, &root));
+    }
+}
@@ -0,0 +1,403 @@
+//! 多消息并行SM3：一次计算多条互不相关消息的摘要
+//!
+//! 多条消息的压缩函数按“通道”交错执行，每个通道对应一条消息：
+//! - 可移植实现：4通道标量交错，依赖编译器自动向量化
+//! - x86_64 且运行时检测到 AVX2：8通道，每个 `__m256i` 存放8条消息的同一个状态字
+//!
+//! 结果与逐条使用 `Sm3` 完全一致，适合大量短消息（如去重分块）。
+
+use super::Sm3;
+
+/// 多通道的32位字向量，各通道互不干扰
+///
+/// # Safety
+///
+/// 实现可以使用特定的CPU指令集（如 `X8` 需要 AVX2），调用任何方法前，调用者必须确认
+/// 当前CPU支持该实现所需的全部特性。
+trait Lanes: Copy {
+    const N: usize;
+
+    unsafe fn splat(x: u32) -> Self;
+    /// `words[l]` 为第l个通道的值，`words` 至少 `N` 个
+    unsafe fn from_words(words: &[u32]) -> Self;
+    /// `out` 至少 `N` 个
+    unsafe fn to_words(self, out: &mut [u32]);
+
+    unsafe fn add(self, o: Self) -> Self;
+    unsafe fn xor(self, o: Self) -> Self;
+    unsafe fn and(self, o: Self) -> Self;
+    unsafe fn or(self, o: Self) -> Self;
+    /// !self & o
+    unsafe fn andnot(self, o: Self) -> Self;
+    unsafe fn rotl(self, r: u32) -> Self;
+}
+
+/// 可移植实现：4通道标量交错
+#[derive(Clone, Copy)]
+struct X4([u32; 4]);
+
+#[inline(always)]
+fn map4(f: impl Fn(usize) -> u32) -> [u32; 4] {
+    [f(0), f(1), f(2), f(3)]
+}
+
+// 纯标量实现，不依赖任何CPU特性
+impl Lanes for X4 {
+    const N: usize = 4;
+
+    #[inline(always)]
+    unsafe fn splat(x: u32) -> Self {
+        X4([x; 4])
+    }
+
+    #[inline(always)]
+    unsafe fn from_words(words: &[u32]) -> Self {
+        X4([words[0], words[1], words[2], words[3]])
+    }
+
+    #[inline(always)]
+    unsafe fn to_words(self, out: &mut [u32]) {
+        out[..4].copy_from_slice(&self.0);
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, o: Self) -> Self {
+        X4(map4(|l| self.0[l].wrapping_add(o.0[l])))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, o: Self) -> Self {
+        X4(map4(|l| self.0[l] ^ o.0[l]))
+    }
+
+    #[inline(always)]
+    unsafe fn and(self, o: Self) -> Self {
+        X4(map4(|l| self.0[l] & o.0[l]))
+    }
+
+    #[inline(always)]
+    unsafe fn or(self, o: Self) -> Self {
+        X4(map4(|l| self.0[l] | o.0[l]))
+    }
+
+    #[inline(always)]
+    unsafe fn andnot(self, o: Self) -> Self {
+        X4(map4(|l| !self.0[l] & o.0[l]))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        X4(map4(|l| self.0[l].rotate_left(r)))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::{Lanes, MessageLane};
+
+    /// AVX2 实现：8通道
+    #[derive(Clone, Copy)]
+    pub(super) struct X8(__m256i);
+
+    // 每个方法都启用 AVX2，按 `Lanes` 的约定只能在确认CPU支持 AVX2 后调用
+    impl Lanes for X8 {
+        const N: usize = 8;
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn splat(x: u32) -> Self {
+            X8(_mm256_set1_epi32(x as i32))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn from_words(words: &[u32]) -> Self {
+            assert!(words.len() >= 8);
+            // SAFETY: 上面已断言至少有8个u32（32字节）可读，loadu 不要求对齐
+            unsafe { X8(_mm256_loadu_si256(words.as_ptr() as *const __m256i)) }
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn to_words(self, out: &mut [u32]) {
+            assert!(out.len() >= 8);
+            // SAFETY: 上面已断言至少有8个u32（32字节）可写，storeu 不要求对齐
+            unsafe { _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, self.0) }
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn add(self, o: Self) -> Self {
+            X8(_mm256_add_epi32(self.0, o.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn xor(self, o: Self) -> Self {
+            X8(_mm256_xor_si256(self.0, o.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn and(self, o: Self) -> Self {
+            X8(_mm256_and_si256(self.0, o.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn or(self, o: Self) -> Self {
+            X8(_mm256_or_si256(self.0, o.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn andnot(self, o: Self) -> Self {
+            X8(_mm256_andnot_si256(self.0, o.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn rotl(self, r: u32) -> Self {
+            let left = _mm256_sll_epi32(self.0, _mm_cvtsi32_si128(r as i32));
+            let right = _mm256_srl_epi32(self.0, _mm_cvtsi32_si128(32 - r as i32));
+            X8(_mm256_or_si256(left, right))
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) fn hash_group(lanes: &[MessageLane], out: &mut [[u8; 32]]) {
+        // SAFETY: 本函数启用了 AVX2，只能在确认CPU支持 AVX2 后调用，满足 X8 的要求
+        unsafe { super::hash_group::<X8>(lanes, out) }
+    }
+
+    pub(super) fn available() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+}
+
+/// 压缩函数 CF 的多通道版本
+///
+/// # Safety
+///
+/// 同 [`Lanes`]：当前CPU必须支持 `L` 所需的特性
+#[inline(always)]
+unsafe fn compress<L: Lanes>(v: &mut [L; 8], block: &[L; 16]) {
+    // SAFETY: 由调用者保证 `L` 所需的CPU特性可用
+    unsafe {
+        let p0 = |x: L| x.xor(x.rotl(9)).xor(x.rotl(17));
+        let p1 = |x: L| x.xor(x.rotl(15)).xor(x.rotl(23));
+
+        let mut w = [L::splat(0); 68];
+        w[..16].copy_from_slice(block);
+        for j in 16..68 {
+            w[j] = p1(w[j - 16].xor(w[j - 9]).xor(w[j - 3].rotl(15)))
+                .xor(w[j - 13].rotl(7))
+                .xor(w[j - 6]);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+        for j in 0..64 {
+            let a12 = a.rotl(12);
+            let ss1 = a12.add(e).add(L::splat(Sm3::T_ROTATED[j])).rotl(7);
+            let ss2 = ss1.xor(a12);
+            let (ff, gg) = if j < 16 {
+                (a.xor(b).xor(c), e.xor(f).xor(g))
+            } else {
+                (a.and(b).or(a.and(c)).or(b.and(c)), e.and(f).or(e.andnot(g)))
+            };
+            let tt1 = ff.add(d).add(ss2).add(w[j].xor(w[j + 4]));
+            let tt2 = gg.add(h).add(ss1).add(w[j]);
+            d = c;
+            c = b.rotl(9);
+            b = a;
+            a = tt1;
+            h = g;
+            g = f.rotl(19);
+            f = e;
+            e = p0(tt2);
+        }
+
+        for (s, x) in v.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.xor(x);
+        }
+    }
+}
+
+/// 单条消息按分组读取：完整分组直接取自消息，末尾1~2个分组为填充
+struct MessageLane<'a> {
+    msg: &'a [u8],
+    full_blocks: usize,
+    tail: [u8; 128],
+    blocks: usize,
+}
+
+impl<'a> MessageLane<'a> {
+    fn new(msg: &'a [u8]) -> Self {
+        let full_blocks = msg.len() / 64;
+        let rest = &msg[full_blocks * 64..];
+        let mut tail = [0u8; 128];
+        tail[..rest.len()].copy_from_slice(rest);
+        tail[rest.len()] = 0x80;
+        let tail_len = if rest.len() < 56 { 64 } else { 128 };
+        let bits = (msg.len() as u64).wrapping_mul(8);
+        tail[tail_len - 8..tail_len].copy_from_slice(&bits.to_be_bytes());
+        MessageLane { msg, full_blocks, tail, blocks: full_blocks + tail_len / 64 }
+    }
+
+    fn block(&self, i: usize) -> &[u8] {
+        if i < self.full_blocks {
+            &self.msg[i * 64..i * 64 + 64]
+        } else {
+            let t = i - self.full_blocks;
+            &self.tail[t * 64..t * 64 + 64]
+        }
+    }
+}
+
+/// 计算至多 `L::N` 条消息的摘要，已结束的通道输入全零分组，其结果不再读取
+///
+/// # Safety
+///
+/// 同 [`Lanes`]：当前CPU必须支持 `L` 所需的特性
+#[inline(always)]
+unsafe fn hash_group<L: Lanes>(lanes: &[MessageLane], out: &mut [[u8; 32]]) {
+    debug_assert!(lanes.len() <= L::N && lanes.len() == out.len());
+    const ZERO: [u8; 64] = [0u8; 64];
+    // SAFETY: 由调用者保证 `L` 所需的CPU特性可用
+    unsafe {
+        let mut v = [L::splat(0); 8];
+        for (k, x) in v.iter_mut().enumerate() {
+            *x = L::splat(Sm3::IV[k]);
+        }
+
+        let max_blocks = lanes.iter().map(|l| l.blocks).max().unwrap_or(0);
+        let mut words = [0u32; 8];
+        let mut state = [[0u32; 8]; 8];
+        for i in 0..max_blocks {
+            let blocks: [&[u8]; 8] =
+                std::array::from_fn(|l| lanes.get(l).filter(|m| i < m.blocks).map_or(&ZERO[..], |m| m.block(i)));
+
+            // 转置：第j个向量存放各通道的第j个消息字
+            let mut w = [L::splat(0); 16];
+            for (j, x) in w.iter_mut().enumerate() {
+                for l in 0..L::N {
+                    let b = &blocks[l][j * 4..j * 4 + 4];
+                    words[l] = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+                }
+                *x = L::from_words(&words[..L::N]);
+            }
+            compress(&mut v, &w);
+
+            // 恰在本分组结束的通道取出结果
+            if lanes.iter().any(|m| m.blocks == i + 1) {
+                for (k, x) in v.iter().enumerate() {
+                    x.to_words(&mut state[k][..L::N]);
+                }
+                for (l, m) in lanes.iter().enumerate() {
+                    if m.blocks == i + 1 {
+                        for k in 0..8 {
+                            out[l][k * 4..k * 4 + 4].copy_from_slice(&state[k][l].to_be_bytes());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 计算一组消息摘要的实现
+type GroupFn = fn(&[MessageLane], &mut [[u8; 32]]);
+
+// 按长度排序后分组，使同组消息长度相近，减少空转的通道
+fn hash_all(messages: &[&[u8]], lanes: usize, group: GroupFn) -> Vec<[u8; 32]> {
+    let mut order: Vec<usize> = (0..messages.len()).collect();
+    order.sort_by_key(|&i| messages[i].len());
+
+    let mut out = vec![[0u8; 32]; messages.len()];
+    let mut digests = [[0u8; 32]; 8];
+    for idx in order.chunks(lanes) {
+        let group_lanes: Vec<MessageLane> = idx.iter().map(|&i| MessageLane::new(messages[i])).collect();
+        group(&group_lanes, &mut digests[..idx.len()]);
+        for (k, &i) in idx.iter().enumerate() {
+            out[i] = digests[k];
+        }
+    }
+    out
+}
+
+// 可移植实现
+fn hash_all_portable(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+    // SAFETY: X4 是纯标量实现，不需要任何CPU特性
+    hash_all(messages, X4::N, |lanes, out| unsafe { hash_group::<X4>(lanes, out) })
+}
+
+/// 并行计算多条独立消息的SM3，返回顺序与输入一致
+pub fn sm3_many(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::available() {
+        // SAFETY: 已在运行时确认CPU支持 AVX2
+        return hash_all(messages, avx2::X8::N, |lanes, out| unsafe { avx2::hash_group(lanes, out) });
+    }
+    hash_all_portable(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+        messages
+            .iter()
+            .map(|m| {
+                let mut sm3 = Sm3::new();
+                sm3.update(m);
+                sm3.finalize()
+            })
+            .collect()
+    }
+
+    fn samples() -> Vec<Vec<u8>> {
+        // 覆盖填充边界与长短混合的情况
+        let lens = [0, 1, 3, 55, 56, 63, 64, 65, 119, 120, 128, 200, 1000, 4096, 7, 0, 300, 64, 56];
+        lens.iter()
+            .enumerate()
+            .map(|(k, &len)| (0..len).map(|i| (i * 31 + k * 7) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_portable_matches_sm3() {
+        let data = samples();
+        let messages: Vec<&[u8]> = data.iter().map(|m| m.as_slice()).collect();
+        assert_eq!(hash_all_portable(&messages), reference(&messages));
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_avx2_matches_sm3() {
+        if !avx2::available() {
+            return;
+        }
+        let data = samples();
+        let messages: Vec<&[u8]> = data.iter().map(|m| m.as_slice()).collect();
+        // SAFETY: 上面已确认CPU支持 AVX2
+        let out = hash_all(&messages, avx2::X8::N, |lanes, out| unsafe { avx2::hash_group(lanes, out) });
+        assert_eq!(out, reference(&messages));
+    }
+
+    #[test]
+    fn test_sm3_many() {
+        assert!(sm3_many(&[]).is_empty());
+
+        let out = sm3_many(&[b"abc", b""]);
+        let hex: Vec<String> = out.iter().map(|d| d.iter().map(|b| format!("{:02X}", b)).collect()).collect();
+        assert_eq!(hex[0], "66C7F0F462EEEDD9D1F2D46BDC10E4E24167C4875CF2F7A2297DA02B8F4BA8E0");
+        assert_eq!(hex[1], "1AB21D8355CFA17F8E61194831E81A8F22BEC8C728FEFB747ED035EB5082AA2B");
+
+        let data = samples();
+        let messages: Vec<&[u8]> = data.iter().map(|m| m.as_slice()).collect();
+        assert_eq!(sm3_many(&messages), reference(&messages));
+    }
+}
@@ -0,0 +1,138 @@
+//! SM3 的 `std::io` 适配：`Sm3` 实现 `Write`，`HashingReader` 在读取时顺带计算摘要
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use super::Sm3;
+
+/// 读取文件时的缓冲区大小
+const FILE_CHUNK: usize = 64 * 1024;
+
+/// 写入即 `update`，可直接用于 `io::copy(&mut reader, &mut sm3)`
+impl Write for Sm3 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 读取包装器：透传底层数据，同时对已读出的字节计算SM3
+pub struct HashingReader<R: Read> {
+    inner: R,
+    sm3: Sm3,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_hasher(inner, Sm3::new())
+    }
+
+    /// 使用已有的上下文（如兼容模式或已吸收前缀的状态）
+    pub fn with_hasher(inner: R, sm3: Sm3) -> Self {
+        HashingReader { inner, sm3 }
+    }
+
+    /// 目前为止已读出数据的摘要，不影响后续读取
+    pub fn finalize(&self) -> [u8; 32] {
+        self.sm3.finalize()
+    }
+
+    /// 拆出底层读取器与哈希上下文
+    pub fn into_parts(self) -> (R, Sm3) {
+        (self.inner, self.sm3)
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sm3.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// 计算任意读取器中全部数据的SM3
+pub fn sm3_reader<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut sm3 = Sm3::new();
+    let mut buf = vec![0u8; FILE_CHUNK];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                sm3.update(&buf[..n]);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(sm3.finalize())
+}
+
+/// 计算文件的SM3
+pub fn sm3_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; 32]> {
+    sm3_reader(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 131 + 7) as u8).collect()
+    }
+
+    fn digest(data: &[u8]) -> [u8; 32] {
+        let mut sm3 = Sm3::new();
+        sm3.update(data);
+        sm3.finalize()
+    }
+
+    #[test]
+    fn test_io_copy_into_sm3() {
+        let data = sample(200_000);
+        let mut sm3 = Sm3::new();
+        let n = io::copy(&mut &data[..], &mut sm3).unwrap();
+        assert_eq!(n, data.len() as u64);
+        assert_eq!(sm3.finalize(), digest(&data));
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let data = sample(100_000);
+        let mut reader = HashingReader::new(&data[..]);
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(reader.finalize(), digest(&data));
+
+        // 只读了一部分时摘要覆盖已读出的部分
+        let mut reader = HashingReader::new(&data[..]);
+        let mut head = [0u8; 1000];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(reader.finalize(), digest(&data[..1000]));
+        let (rest, _) = reader.into_parts();
+        assert_eq!(rest.len(), data.len() - 1000);
+    }
+
+    #[test]
+    fn test_sm3_file() {
+        let data = sample(FILE_CHUNK * 2 + 123);
+        let path = std::env::temp_dir().join(format!("gm-rust-sm3-{}.bin", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        let result = sm3_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), digest(&data));
+
+        assert!(sm3_file(std::env::temp_dir().join("gm-rust-no-such-file")).is_err());
+    }
+}
@@ -4,19 +4,60 @@
 use std::error::Error;
 use std::fmt;
 
+mod bitsliced;
+pub mod block;
+pub mod gcm;
+pub mod mac;
+pub mod parallel;
+pub mod stream;
+mod ttable;
+
+pub use bitsliced::Sm4Bitsliced;
+pub use block::{Backend, BlockCipher};
+pub use gcm::Sm4Gcm;
+pub use ttable::Sm4TTable;
+
+// S盒
+const S_TABLE: [u8; 256] = [
+    0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
+    0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+    0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
+    0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
+    0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
+    0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
+    0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
+    0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
+    0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
+    0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
+    0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
+    0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
+    0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
+    0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
+    0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
+    0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48
+];
+
 /// SM4 算法实现
+///
+/// CBC 辅助函数与 `cipher` trait 均使用T表实现，查表下标与密钥相关，
+/// 对缓存计时侧信道敏感的场景请使用 [`Sm4Bitsliced`]
+#[derive(Clone)]
 pub struct SM4 {
     rk: [u32; 32],  // 轮密钥
-    iv: [u8; 16],   // CBC初始向量
+    iv: Option<[u8; 16]>,   // CBC默认IV，未设置时 `encrypt`/`decrypt` 拒绝工作
 }
 
 /// 自定义错误类型
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SM4Error {
     InvalidKeyLength,
     InvalidIVLength,
     InvalidHexString,
     InvalidPadding,
+    InvalidDataLength,
+    DataTooLong,
+    AuthenticationFailed,
+    MissingIV,
 }
 
 impl fmt::Display for SM4Error {
@@ -26,97 +67,139 @@ impl fmt::Display for SM4Error {
             SM4Error::InvalidIVLength => write!(f, "Invalid IV length, expected 16 bytes"),
             SM4Error::InvalidHexString => write!(f, "Invalid hex string"),
             SM4Error::InvalidPadding => write!(f, "Invalid padding"),
+            SM4Error::InvalidDataLength => write!(f, "Invalid data length, expected a multiple of 16 bytes"),
+            SM4Error::DataTooLong => write!(f, "Data exceeds the length limit of the mode"),
+            SM4Error::AuthenticationFailed => write!(f, "Authentication tag mismatch"),
+            SM4Error::MissingIV => write!(f, "No default IV set, use set_key/set_iv or the *_with_iv methods"),
         }
     }
 }
 
 impl Error for SM4Error {}
 
+impl Default for SM4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SM4 {
     /// 创建新的SM4实例
     pub fn new() -> Self {
         SM4 {
             rk: [0; 32],
-            iv: [0; 16],
+            iv: None,
+        }
+    }
+
+    /// 使用16字节密钥创建SM4实例
+    ///
+    /// 该实例没有默认IV：应通过 `*_with_iv` 为每条消息指定IV，
+    /// 或先调用 `set_iv`，否则 `encrypt`/`decrypt` 返回 [`SM4Error::MissingIV`]
+    pub fn with_key(key: &[u8]) -> Result<Self, SM4Error> {
+        if key.len() != 16 {
+            return Err(SM4Error::InvalidKeyLength);
         }
+        let mut sm4 = Self::new();
+        sm4.init_key(key);
+        Ok(sm4)
+    }
+
+    // 由定长密钥直接创建（无默认IV），供各子模块使用
+    fn from_key(key: &[u8; 16]) -> Self {
+        let mut sm4 = Self::new();
+        sm4.init_key(key);
+        sm4
     }
 
-    /// 设置密钥和IV
+    /// 设置密钥和IV，密钥与IV都必须为16字节
     pub fn set_key(&mut self, key: &[u8], iv: &[u8]) -> Result<(), SM4Error> {
-        // 处理密钥
-        let key_bytes = if key.len() != 16 {
-            // 使用SM3哈希的十六进制字符串的ASCII字节（匹配Java行为）
-            let mut sm3 = crate::sm3::Sm3::new();
-            sm3.update(key);
-            sm3.finish();
-            let hash_hex = sm3.hash_hex_upper();
-            let hash_hex_bytes = hash_hex.as_bytes();
-            let mut new_key = [0; 16];
-            new_key.copy_from_slice(&hash_hex_bytes[..16]);
-            new_key
-        } else {
-            let mut new_key = [0; 16];
-            new_key.copy_from_slice(key);
-            new_key
-        };
+        if key.len() != 16 {
+            return Err(SM4Error::InvalidKeyLength);
+        }
+        let iv = iv.try_into().map_err(|_| SM4Error::InvalidIVLength)?;
+        self.init_key(key);
+        self.iv = Some(iv);
+        Ok(())
+    }
+
+    /// 旧版兼容模式：长度不为16字节的密钥/IV取SM3大写十六进制摘要的前16个ASCII字符（匹配Java行为）
+    ///
+    /// 该方式派生出的密钥仅约64位熵，仅用于与旧系统互通，新代码请使用 `set_key`
+    pub fn set_key_legacy(&mut self, key: &[u8], iv: &[u8]) {
+        let key_bytes = Self::legacy_derive(key);
+        let iv_bytes = Self::legacy_derive(iv);
+        self.init_key(&key_bytes);
+        self.iv = Some(iv_bytes);
+    }
 
-        // 处理IV
-        let iv_bytes = if iv.len() != 16 {
-            // 使用SM3哈希的十六进制字符串的ASCII字节（匹配Java行为）
+    // 旧版派生：16字节原样使用，否则使用SM3哈希的十六进制字符串的ASCII字节
+    fn legacy_derive(input: &[u8]) -> [u8; 16] {
+        let mut out = [0; 16];
+        if input.len() == 16 {
+            out.copy_from_slice(input);
+        } else {
             let mut sm3 = crate::sm3::Sm3::new();
-            sm3.update(iv);
+            sm3.update(input);
             sm3.finish();
-            let hash_hex = sm3.hash_hex_upper();
-            let hash_hex_bytes = hash_hex.as_bytes();
-            let mut new_iv = [0; 16];
-            new_iv.copy_from_slice(&hash_hex_bytes[..16]);
-            new_iv
-        } else {
-            let mut new_iv = [0; 16];
-            new_iv.copy_from_slice(iv);
-            new_iv
-        };
+            out.copy_from_slice(&sm3.hash_hex_upper().as_bytes()[..16]);
+        }
+        out
+    }
 
-        self.init_key(&key_bytes, &iv_bytes);
+    /// 设置默认IV（`encrypt`/`decrypt` 使用）
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), SM4Error> {
+        self.iv = Some(iv.try_into().map_err(|_| SM4Error::InvalidIVLength)?);
         Ok(())
     }
 
-    /// 加密字符串
+    /// 以默认IV加密字符串，未设置IV时返回 [`SM4Error::MissingIV`]
     pub fn encrypt(&self, plaintext: &str) -> Result<String, Box<dyn Error>> {
-        let input = plaintext.as_bytes();
-        let padded = self.pkcs7_pad(input);
-        let mut iv = self.iv;
-        let mut output = Vec::new();
+        let iv = self.iv.as_ref().ok_or(SM4Error::MissingIV)?;
+        Ok(bytes_to_hex(&self.encrypt_with_iv(plaintext.as_bytes(), iv)))
+    }
+
+    /// 以默认IV解密字符串，未设置IV时返回 [`SM4Error::MissingIV`]
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, Box<dyn Error>> {
+        let iv = self.iv.as_ref().ok_or(SM4Error::MissingIV)?;
+        let input = hex_to_bytes(ciphertext)?;
+        let unpadded = self.decrypt_with_iv(&input, iv)?;
+        Ok(String::from_utf8(unpadded)?)
+    }
+
+    /// 使用指定IV进行CBC加密（PKCS#7填充）
+    pub fn encrypt_with_iv(&self, plaintext: &[u8], iv: &[u8; 16]) -> Vec<u8> {
+        let padded = self.pkcs7_pad(plaintext);
+        let table = Sm4TTable::from(self);
+        let mut iv = *iv;
+        let mut output = Vec::with_capacity(padded.len());
 
         for chunk in padded.chunks(16) {
-            let block = self.cbc_encrypt_block(chunk, &iv)?;
-            output.extend_from_slice(&block);
-            iv.copy_from_slice(&block);
+            let mut block = [0; 16];
+            for i in 0..16 {
+                block[i] = chunk[i] ^ iv[i];
+            }
+            iv = table.encrypt_block(&block);
+            output.extend_from_slice(&iv);
         }
 
-        Ok(bytes_to_hex(&output))
+        output
     }
 
-    /// 解密字符串
-    pub fn decrypt(&self, ciphertext: &str) -> Result<String, Box<dyn Error>> {
-        let input = hex_to_bytes(ciphertext)?;
-        let mut iv = self.iv;
-        let mut output = Vec::new();
-        let mut prev_block = [0; 16];
-
-        for chunk in input.chunks(16) {
-            let block = self.cbc_decrypt_block(chunk, &iv)?;
-            output.extend_from_slice(&block);
-            prev_block.copy_from_slice(chunk);
-            iv.copy_from_slice(&prev_block);
+    /// 使用指定IV进行CBC解密并去除PKCS#7填充
+    pub fn decrypt_with_iv(&self, ciphertext: &[u8], iv: &[u8; 16]) -> Result<Vec<u8>, SM4Error> {
+        if !ciphertext.len().is_multiple_of(16) {
+            return Err(SM4Error::InvalidDataLength);
         }
+        // CBC解密可多分组并行，交给T表实现按批处理
+        let mut output = ciphertext.to_vec();
+        parallel::cbc_decrypt_in_place(&Sm4TTable::from(self), iv, &mut output)?;
 
-        let unpadded = self.pkcs7_unpad(&output)?;
-        Ok(String::from_utf8(unpadded)?)
+        self.pkcs7_unpad(&output)
     }
 
     // 初始化密钥
-    fn init_key(&mut self, key: &[u8], iv: &[u8]) {
+    fn init_key(&mut self, key: &[u8]) {
         // FK值应与Java版本一致
         const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
 
@@ -153,31 +236,10 @@ impl SM4 {
             k[i+4] = k[i] ^ self.t_prime(input);
             self.rk[i] = k[i+4];
         }
-
-        // 设置IV
-        self.iv.copy_from_slice(iv);
     }
 
     // S盒
     fn sbox(&self, input: u8) -> u8 {
-        const S_TABLE: [u8; 256] = [
-            0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
-            0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
-            0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
-            0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
-            0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
-            0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
-            0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
-            0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
-            0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
-            0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
-            0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
-            0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
-            0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
-            0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
-            0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
-            0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48
-        ];
         S_TABLE[input as usize]
     }
 
@@ -221,22 +283,24 @@ impl SM4 {
     // 反序变换 R
     fn r(&self, a: &mut [u32; 4]) {
         // 交换X0和X3
-        let temp = a[0];
-        a[0] = a[3];
-        a[3] = temp;
+        a.swap(0, 3);
 
         // 交换X1和X2
-        let temp = a[1];
-        a[1] = a[2];
-        a[2] = temp;
+        a.swap(1, 2);
     }
 
-    // CBC模式加密一个块
-    fn cbc_encrypt_block(&self, block: &[u8], iv: &[u8]) -> Result<[u8; 16], SM4Error> {
-        if block.len() != 16 {
-            return Err(SM4Error::InvalidKeyLength);
-        }
+    /// 加密单个16字节分组（ECB原语，不含工作模式与填充）
+    pub fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        self.crypt_block(block, false)
+    }
+
+    /// 解密单个16字节分组（ECB原语，不含工作模式与填充）
+    pub fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        self.crypt_block(block, true)
+    }
 
+    // 32轮迭代 + 反序变换，解密时轮密钥逆序使用
+    fn crypt_block(&self, block: &[u8; 16], decrypt: bool) -> [u8; 16] {
         // 转换为u32数组（与Java版本一致）
         let mut x = [0; 4];
         for i in 0..4 {
@@ -246,21 +310,13 @@ impl SM4 {
                    (block[i*4+3] as u32);
         }
 
-        // 先异或IV再转换（与Java版本一致）
-        for i in 0..4 {
-            let iv_word = ((iv[i*4] as u32) << 24) |
-                          ((iv[i*4+1] as u32) << 16) |
-                          ((iv[i*4+2] as u32) << 8) |
-                          (iv[i*4+3] as u32);
-            x[i] ^= iv_word;
-        }
-
-        // 32轮加密 (与Java版本一致)
+        // 32轮迭代 (与Java版本一致)
         let mut x_next = [0; 36];
         x_next[..4].copy_from_slice(&x);
 
         for i in 0..32 {
-            x_next[i+4] = self.f(x_next[i], x_next[i+1], x_next[i+2], x_next[i+3], self.rk[i]);
+            let rk = if decrypt { self.rk[31 - i] } else { self.rk[i] };
+            x_next[i+4] = self.f(x_next[i], x_next[i+1], x_next[i+2], x_next[i+3], rk);
         }
 
         // 反序变换 (与Java版本一致)
@@ -270,66 +326,21 @@ impl SM4 {
         // 转换为字节数组（大端序）
         let mut output = [0; 16];
         for i in 0..4 {
-            let bytes = xo[i].to_be_bytes();
-            output[i*4] = bytes[0];
-            output[i*4+1] = bytes[1];
-            output[i*4+2] = bytes[2];
-            output[i*4+3] = bytes[3];
-        }
-
-        Ok(output)
-    }
-
-    // CBC模式解密一个块
-    fn cbc_decrypt_block(&self, block: &[u8], iv: &[u8]) -> Result<[u8; 16], SM4Error> {
-        if block.len() != 16 {
-            return Err(SM4Error::InvalidKeyLength);
-        }
-
-        // 转换为u32数组
-        let mut x = [0; 4];
-        for i in 0..4 {
-            x[i] = (block[i*4] as u32) << 24 | 
-                   (block[i*4+1] as u32) << 16 | 
-                   (block[i*4+2] as u32) << 8 | 
-                   (block[i*4+3] as u32);
-        }
-
-        // 32轮解密
-        let mut x_next = [0; 36];
-        x_next[..4].copy_from_slice(&x);
-        
-        for i in 0..32 {
-            x_next[i+4] = self.f(x_next[i], x_next[i+1], x_next[i+2], x_next[i+3], self.rk[31-i]);
-        }
-
-        // 反序变换
-        let mut xo = [x_next[32], x_next[33], x_next[34], x_next[35]];
-        self.r(&mut xo);
-
-        // 转换为字节数组并与IV异或
-        let mut output = [0; 16];
-        for i in 0..4 {
-            let bytes = xo[i].to_be_bytes();
-            output[i*4] = bytes[0] ^ iv[i*4];
-            output[i*4+1] = bytes[1] ^ iv[i*4+1];
-            output[i*4+2] = bytes[2] ^ iv[i*4+2];
-            output[i*4+3] = bytes[3] ^ iv[i*4+3];
+            output[i*4..i*4+4].copy_from_slice(&xo[i].to_be_bytes());
         }
-
-        Ok(output)
+        output
     }
 
     // PKCS#7填充
     fn pkcs7_pad(&self, input: &[u8]) -> Vec<u8> {
         let block_size = 16;
-        let pad_len = if input.len() % block_size == 0 {
+        let pad_len = if input.len().is_multiple_of(block_size) {
             block_size  // 如果已经是块大小的整数倍，填充一个完整块
         } else {
             block_size - (input.len() % block_size)
         };
         let mut output = input.to_vec();
-        output.extend(std::iter::repeat(pad_len as u8).take(pad_len));
+        output.extend(std::iter::repeat_n(pad_len as u8, pad_len));
         output
     }
 
@@ -348,10 +359,8 @@ impl SM4 {
             return Err(SM4Error::InvalidPadding);
         }
         
-        for i in (input.len() - pad_len)..input.len() {
-            if input[i] as usize != pad_len {
-                return Err(SM4Error::InvalidPadding);
-            }
+        if input[input.len() - pad_len..].iter().any(|&b| b as usize != pad_len) {
+            return Err(SM4Error::InvalidPadding);
         }
         
         Ok(input[..input.len() - pad_len].to_vec())
@@ -369,7 +378,7 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
 
 // 16进制字符串转字节数组
 fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, SM4Error> {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return Err(SM4Error::InvalidHexString);
     }
     
@@ -382,6 +391,217 @@ fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, SM4Error> {
     Ok(bytes)
 }
 
+/// RustCrypto `cipher` 0.4 trait 实现，可配合 `cbc`、`ctr` 等模式 crate 使用
+///
+/// `SM4` 与 `Sm4TTable` 使用T表实现（查表与密钥相关），`Sm4Bitsliced` 使用常量时间的位切片实现。
+/// 位切片每次变换固定计算64个分组，单分组吞吐量约为T表的1/100（约1 MiB/s 对 100 MiB/s），
+/// 64分组批处理时约为T表的1/5，CBC加密等只能逐块处理的模式不宜使用。
+/// 批量处理时按64个分组交给底层实现，结果与参考实现一致
+#[cfg(feature = "cipher")]
+mod cipher_impl {
+    use cipher::consts::{U16, U64};
+    use cipher::inout::InOut;
+    use cipher::{
+        Block, BlockBackend, BlockClosure, BlockDecrypt, BlockEncrypt, BlockSizeUser, Key, KeyInit, KeySizeUser,
+        ParBlocks, ParBlocksSizeUser,
+    };
+
+    use super::block::BlockCipher;
+    use super::{SM4, Sm4Bitsliced, Sm4TTable};
+
+    // 各类型的 trait 实现仅在密钥初始化与底层实现上不同
+    macro_rules! impl_cipher {
+        ($ty:ty, $init:expr, $backend:expr) => {
+            impl KeySizeUser for $ty {
+                type KeySize = U16;
+            }
+
+            impl KeyInit for $ty {
+                fn new(key: &Key<Self>) -> Self {
+                    $init(&(*key).into())
+                }
+            }
+
+            impl BlockSizeUser for $ty {
+                type BlockSize = U16;
+            }
+
+            impl cipher::BlockCipher for $ty {}
+
+            impl BlockEncrypt for $ty {
+                fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = U16>) {
+                    f.call(&mut Backend { cipher: &$backend(self), decrypt: false });
+                }
+            }
+
+            impl BlockDecrypt for $ty {
+                fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = U16>) {
+                    f.call(&mut Backend { cipher: &$backend(self), decrypt: true });
+                }
+            }
+        };
+    }
+
+    impl_cipher!(SM4, SM4::from_key, Sm4TTable::from);
+    impl_cipher!(Sm4TTable, Sm4TTable::new, |c: &Sm4TTable| c.clone());
+    impl_cipher!(Sm4Bitsliced, Sm4Bitsliced::new, |c: &Sm4Bitsliced| c.clone());
+
+    struct Backend<'a, C: BlockCipher> {
+        cipher: &'a C,
+        decrypt: bool,
+    }
+
+    impl<C: BlockCipher> BlockSizeUser for Backend<'_, C> {
+        type BlockSize = U16;
+    }
+
+    impl<C: BlockCipher> ParBlocksSizeUser for Backend<'_, C> {
+        type ParBlocksSize = U64;
+    }
+
+    impl<C: BlockCipher> BlockBackend for Backend<'_, C> {
+        fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+            let input: [u8; 16] = (*block.get_in()).into();
+            let output = if self.decrypt {
+                self.cipher.decrypt_block(&input)
+            } else {
+                self.cipher.encrypt_block(&input)
+            };
+            *block.get_out() = output.into();
+        }
+
+        fn proc_par_blocks(&mut self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+            let mut buf = [[0u8; 16]; 64];
+            for (b, input) in buf.iter_mut().zip(blocks.get_in().iter()) {
+                *b = (*input).into();
+            }
+            if self.decrypt {
+                self.cipher.decrypt_blocks(&mut buf);
+            } else {
+                self.cipher.encrypt_blocks(&mut buf);
+            }
+            for (out, b) in blocks.get_out().iter_mut().zip(buf) {
+                *out = b.into();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use cipher::consts::U16;
+        use cipher::generic_array::GenericArray;
+        use cipher::{BlockDecrypt, BlockEncrypt, BlockSizeUser, KeyInit};
+
+        use super::{SM4, Sm4Bitsliced, Sm4TTable};
+
+        fn roundtrip<C>(key: &[u8], blocks: &mut [[u8; 16]]) -> Vec<[u8; 16]>
+        where
+            C: KeyInit + BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16>,
+        {
+            let c = C::new_from_slice(key).unwrap();
+            let mut ga: Vec<_> = blocks.iter().map(|b| *GenericArray::from_slice(b)).collect();
+            c.encrypt_blocks(&mut ga);
+            let out = ga.iter().map(|b| (*b).into()).collect();
+            c.decrypt_blocks(&mut ga);
+            for (b, g) in blocks.iter_mut().zip(ga) {
+                *b = g.into();
+            }
+            out
+        }
+
+        #[test]
+        fn test_cipher_traits() {
+            let key = [
+                0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+                0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+            ];
+            let reference = SM4::from_key(&key);
+            // 70个分组：覆盖64分组并行路径与剩余的单分组路径
+            let original: Vec<[u8; 16]> = (0..70u8).map(|i| [i.wrapping_mul(37); 16]).collect();
+            let expected: Vec<[u8; 16]> = original.iter().map(|b| reference.encrypt_block(b)).collect();
+            for roundtrip in [roundtrip::<SM4>, roundtrip::<Sm4TTable>, roundtrip::<Sm4Bitsliced>] {
+                let mut blocks = original.clone();
+                assert_eq!(roundtrip(&key, &mut blocks), expected);
+                assert_eq!(blocks, original);
+            }
+            assert!(<SM4 as KeyInit>::new_from_slice(&key[..15]).is_err());
+        }
+    }
+}
+
+/// RustCrypto `aead` 0.5 trait 实现（SM4-GCM，12字节nonce）
+#[cfg(feature = "aead")]
+mod aead_impl {
+    use aead::consts::{U0, U12, U16};
+    use aead::{AeadCore, AeadInPlace, Key, KeyInit, KeySizeUser, Nonce, Tag};
+
+    use super::Sm4Gcm;
+
+    impl KeySizeUser for Sm4Gcm {
+        type KeySize = U16;
+    }
+
+    impl KeyInit for Sm4Gcm {
+        fn new(key: &Key<Self>) -> Self {
+            Sm4Gcm::new(&(*key).into())
+        }
+    }
+
+    impl AeadCore for Sm4Gcm {
+        type NonceSize = U12;
+        type TagSize = U16;
+        type CiphertextOverhead = U0;
+    }
+
+    impl AeadInPlace for Sm4Gcm {
+        fn encrypt_in_place_detached(
+            &self,
+            nonce: &Nonce<Self>,
+            associated_data: &[u8],
+            buffer: &mut [u8],
+        ) -> aead::Result<Tag<Self>> {
+            Sm4Gcm::encrypt_in_place_detached(self, nonce, associated_data, buffer)
+                .map(Tag::<Self>::from)
+                .map_err(|_| aead::Error)
+        }
+
+        fn decrypt_in_place_detached(
+            &self,
+            nonce: &Nonce<Self>,
+            associated_data: &[u8],
+            buffer: &mut [u8],
+            tag: &Tag<Self>,
+        ) -> aead::Result<()> {
+            Sm4Gcm::decrypt_in_place_detached(self, nonce, associated_data, buffer, tag).map_err(|_| aead::Error)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use aead::{Aead, KeyInit, Payload};
+
+        use super::Sm4Gcm;
+
+        fn seal<A: Aead + KeyInit>(key: &[u8], nonce: &[u8], aad: &[u8], msg: &[u8]) -> Vec<u8> {
+            let a = A::new_from_slice(key).unwrap();
+            a.encrypt(nonce.into(), Payload { msg, aad }).unwrap()
+        }
+
+        #[test]
+        fn test_aead_traits() {
+            let key = b"0123456789abcdef";
+            let nonce = [3u8; 12];
+            let ct = seal::<Sm4Gcm>(key, &nonce, b"aad", b"hello aead");
+            assert_eq!(ct, Sm4Gcm::new(key).encrypt(&nonce, b"aad", b"hello aead").unwrap());
+
+            let a = <Sm4Gcm as KeyInit>::new(key.into());
+            let pt = Aead::decrypt(&a, (&nonce).into(), Payload { msg: &ct, aad: b"aad" }).unwrap();
+            assert_eq!(pt, b"hello aead");
+            assert!(Aead::decrypt(&a, (&nonce).into(), Payload { msg: &ct, aad: b"bad" }).is_err());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,7 +614,7 @@ mod tests {
 
         // 测试SM4加密
         let mut sm4 = SM4::new();
-        sm4.set_key(key.as_bytes(), iv.as_bytes()).unwrap();
+        sm4.set_key_legacy(key.as_bytes(), iv.as_bytes());
 
         let plaintext = "国密SM4对称加密算法";
 
@@ -407,4 +627,75 @@ mod tests {
         let decrypted = sm4.decrypt(&ciphertext).unwrap();
         assert_eq!(plaintext, decrypted);
     }
+
+    #[test]
+    fn test_block_standard_vector() {
+        // GB/T 32907 附录A 示例1
+        let key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+            0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+        ];
+        let expected = [
+            0x68, 0x1e, 0xdf, 0x34, 0xd2, 0x06, 0x96, 0x5e,
+            0x86, 0xb3, 0xe9, 0x4f, 0x53, 0x6e, 0x42, 0x46,
+        ];
+        let mut sm4 = SM4::new();
+        sm4.set_key(&key, &[0; 16]).unwrap();
+        let c = sm4.encrypt_block(&key);
+        assert_eq!(c, expected);
+        assert_eq!(sm4.decrypt_block(&c), key);
+    }
+
+    #[test]
+    fn test_strict_key_length() {
+        let mut sm4 = SM4::new();
+        assert!(matches!(sm4.set_key(b"this is the key", &[0; 16]), Err(SM4Error::InvalidKeyLength)));
+        assert!(matches!(sm4.set_key(&[0; 16], b"short iv"), Err(SM4Error::InvalidIVLength)));
+        assert!(matches!(SM4::with_key(&[0; 32]), Err(SM4Error::InvalidKeyLength)));
+        assert!(sm4.set_key(&[0; 16], &[0; 16]).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_key_matches_explicit_derivation() {
+        // 旧版派生结果等价于显式传入SM3十六进制摘要的前16个字符
+        let mut legacy = SM4::new();
+        legacy.set_key_legacy(b"this is the key", b"this is the iv");
+
+        let derive = |input: &str| {
+            let mut sm3 = crate::sm3::Sm3::new();
+            sm3.update_str(input).finish();
+            sm3.hash_hex_upper()[..16].to_string()
+        };
+        let mut strict = SM4::new();
+        strict
+            .set_key(derive("this is the key").as_bytes(), derive("this is the iv").as_bytes())
+            .unwrap();
+
+        assert_eq!(legacy.encrypt("abc").unwrap(), strict.encrypt("abc").unwrap());
+    }
+
+    #[test]
+    fn test_per_message_iv() {
+        let sm4 = SM4::with_key(b"0123456789abcdef").unwrap();
+        let iv1 = [1u8; 16];
+        let iv2 = [2u8; 16];
+        let msg = b"per message iv test";
+
+        let c1 = sm4.encrypt_with_iv(msg, &iv1);
+        let c2 = sm4.encrypt_with_iv(msg, &iv2);
+        assert_ne!(c1, c2);
+        assert_eq!(sm4.decrypt_with_iv(&c1, &iv1).unwrap(), msg);
+        assert_eq!(sm4.decrypt_with_iv(&c2, &iv2).unwrap(), msg);
+        assert!(matches!(sm4.decrypt_with_iv(&c1[..20], &iv1), Err(SM4Error::InvalidDataLength)));
+
+        // 仅有密钥的实例没有默认IV
+        let mut sm4 = SM4::with_key(b"0123456789abcdef").unwrap();
+        assert_eq!(sm4.encrypt("x").unwrap_err().downcast_ref(), Some(&SM4Error::MissingIV));
+        assert_eq!(sm4.decrypt(&bytes_to_hex(&c1)).unwrap_err().downcast_ref(), Some(&SM4Error::MissingIV));
+        assert_eq!(SM4::new().encrypt("x").unwrap_err().downcast_ref(), Some(&SM4Error::MissingIV));
+
+        // 默认IV与按消息IV结果一致
+        sm4.set_iv(&iv1).unwrap();
+        assert_eq!(sm4.encrypt("per message iv test").unwrap(), bytes_to_hex(&c1));
+    }
 }
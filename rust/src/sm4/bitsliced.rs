@@ -0,0 +1,294 @@
+//! SM4 位切片实现
+//!
+//! 64个分组按位转置存放：状态字的每一位是一个u64，其第l位属于第l个分组。
+//! S盒按其代数结构 S(x) = A·(A·x ⊕ C)⁻¹ ⊕ C（GF(2^8)，模多项式 x^8+x^7+x^6+x^5+x^4+x^2+1，
+//! C = 0xD3）以布尔电路计算，循环移位变为位平面下标置换，全程无秘密相关的查表与分支。
+
+use super::SM4;
+use super::block::BlockCipher;
+
+/// 并行处理的分组数
+const LANES: usize = 64;
+
+/// 仿射变换矩阵A：输出第j位 = parity(x & A_ROWS[j])
+const A_ROWS: [u8; 8] = [0xa7, 0x4f, 0x9e, 0x3d, 0x7a, 0xf4, 0xe9, 0xd3];
+const C: u8 = 0xd3;
+/// 模多项式的低8位（x^8 = x^7+x^6+x^5+x^4+x^2+1）
+const POLY_LOW: u8 = 0xf5;
+
+type Planes = [u64; 8];
+
+// GF(2)上的仿射变换 A·x ⊕ C，矩阵与常量均为公开值
+fn affine(x: &Planes) -> Planes {
+    let mut out = [0u64; 8];
+    for j in 0..8 {
+        let mut acc = 0u64;
+        for (k, &plane) in x.iter().enumerate() {
+            if (A_ROWS[j] >> k) & 1 == 1 {
+                acc ^= plane;
+            }
+        }
+        if (C >> j) & 1 == 1 {
+            acc = !acc;
+        }
+        out[j] = acc;
+    }
+    out
+}
+
+// 15位乘积按模多项式约减到8位
+fn reduce(mut p: [u64; 15]) -> Planes {
+    for k in (8..15).rev() {
+        for j in 0..8 {
+            if (POLY_LOW >> j) & 1 == 1 {
+                p[k - 8 + j] ^= p[k];
+            }
+        }
+    }
+    let mut out = [0u64; 8];
+    out.copy_from_slice(&p[..8]);
+    out
+}
+
+fn gf_mul(a: &Planes, b: &Planes) -> Planes {
+    let mut p = [0u64; 15];
+    for i in 0..8 {
+        for j in 0..8 {
+            p[i + j] ^= a[i] & b[j];
+        }
+    }
+    reduce(p)
+}
+
+// 平方在GF(2)上是线性的，只需重排位后约减
+fn gf_square(a: &Planes) -> Planes {
+    let mut p = [0u64; 15];
+    for i in 0..8 {
+        p[2 * i] = a[i];
+    }
+    reduce(p)
+}
+
+// 求逆：x^254 = x^-1（0映射为0）
+fn gf_inv(x: &Planes) -> Planes {
+    let x2 = gf_square(x);
+    let x3 = gf_mul(&x2, x);
+    let x12 = gf_square(&gf_square(&x3));
+    let x15 = gf_mul(&x12, &x3);
+    let mut x240 = x15;
+    for _ in 0..4 {
+        x240 = gf_square(&x240);
+    }
+    let x252 = gf_mul(&x240, &x12);
+    gf_mul(&x252, &x2)
+}
+
+fn sbox(x: &Planes) -> Planes {
+    affine(&gf_inv(&affine(x)))
+}
+
+/// 32位字的位平面表示，下标为位序号（0为最低位）
+type Word = [u64; 32];
+
+// 非线性变换 τ：4个字节分别过S盒
+fn tau(a: &Word) -> Word {
+    let mut out = [0u64; 32];
+    for byte in 0..4 {
+        let mut x = [0u64; 8];
+        x.copy_from_slice(&a[byte * 8..byte * 8 + 8]);
+        out[byte * 8..byte * 8 + 8].copy_from_slice(&sbox(&x));
+    }
+    out
+}
+
+// 循环左移r位：输出第p位取输入第(p - r)位
+fn rotl(a: &Word, r: usize) -> Word {
+    let mut out = [0u64; 32];
+    for p in 0..32 {
+        out[p] = a[(p + 32 - r) % 32];
+    }
+    out
+}
+
+// 线性变换 L 与 L'
+fn l(b: &Word) -> Word {
+    let (r2, r10, r18, r24) = (rotl(b, 2), rotl(b, 10), rotl(b, 18), rotl(b, 24));
+    let mut out = [0u64; 32];
+    for p in 0..32 {
+        out[p] = b[p] ^ r2[p] ^ r10[p] ^ r18[p] ^ r24[p];
+    }
+    out
+}
+
+fn l_prime(b: &Word) -> Word {
+    let (r13, r23) = (rotl(b, 13), rotl(b, 23));
+    let mut out = [0u64; 32];
+    for p in 0..32 {
+        out[p] = b[p] ^ r13[p] ^ r23[p];
+    }
+    out
+}
+
+// 常量（轮密钥）广播到所有分组：位为1时对应位平面全1
+fn broadcast(v: u32) -> Word {
+    std::array::from_fn(|p| 0u64.wrapping_sub(((v >> p) & 1) as u64))
+}
+
+fn xor4(a: &Word, b: &Word, c: &Word, d: &Word) -> Word {
+    let mut out = [0u64; 32];
+    for p in 0..32 {
+        out[p] = a[p] ^ b[p] ^ c[p] ^ d[p];
+    }
+    out
+}
+
+// 单字转为位平面（只使用第0个分组）
+fn to_planes(v: u32) -> Word {
+    std::array::from_fn(|p| ((v >> p) & 1) as u64)
+}
+
+fn from_planes(w: &Word) -> u32 {
+    let mut v = 0u32;
+    for (p, &plane) in w.iter().enumerate() {
+        v |= ((plane & 1) as u32) << p;
+    }
+    v
+}
+
+/// SM4 位切片实现
+#[derive(Clone)]
+pub struct Sm4Bitsliced {
+    rk: [u32; 32],
+}
+
+impl Sm4Bitsliced {
+    /// 密钥扩展同样使用位切片S盒，避免密钥相关的查表
+    pub fn new(key: &[u8; 16]) -> Self {
+        const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
+
+        let mut k = [0u32; 36];
+        for i in 0..4 {
+            let mk = u32::from_be_bytes([key[i * 4], key[i * 4 + 1], key[i * 4 + 2], key[i * 4 + 3]]);
+            k[i] = mk ^ FK[i];
+        }
+
+        let mut rk = [0u32; 32];
+        for i in 0..32 {
+            // CK_i 的第j字节为 (4i + j) * 7 mod 256
+            let mut ck = 0u32;
+            for j in 0..4 {
+                ck = (ck << 8) | (((4 * i + j) * 7) & 0xFF) as u32;
+            }
+            let input = k[i + 1] ^ k[i + 2] ^ k[i + 3] ^ ck;
+            let t = from_planes(&l_prime(&tau(&to_planes(input))));
+            k[i + 4] = k[i] ^ t;
+            rk[i] = k[i + 4];
+        }
+
+        Sm4Bitsliced { rk }
+    }
+
+    // 处理至多64个分组
+    fn crypt_batch(&self, blocks: &mut [[u8; 16]], decrypt: bool) {
+        debug_assert!(blocks.len() <= LANES);
+
+        // 转置：x[i][p] 的第l位 = 第l个分组第i个字的第p位
+        let mut x = [[0u64; 32]; 4];
+        for (lane, block) in blocks.iter().enumerate() {
+            for i in 0..4 {
+                let w = u32::from_be_bytes([block[i * 4], block[i * 4 + 1], block[i * 4 + 2], block[i * 4 + 3]]);
+                for (p, plane) in x[i].iter_mut().enumerate() {
+                    *plane |= (((w >> p) & 1) as u64) << lane;
+                }
+            }
+        }
+
+        for r in 0..32 {
+            let rk = if decrypt { self.rk[31 - r] } else { self.rk[r] };
+            let t = l(&tau(&xor4(&x[1], &x[2], &x[3], &broadcast(rk))));
+            let mut next = [0u64; 32];
+            for p in 0..32 {
+                next[p] = x[0][p] ^ t[p];
+            }
+            x = [x[1], x[2], x[3], next];
+        }
+
+        // 反序变换 R 并逆转置
+        for (lane, block) in blocks.iter_mut().enumerate() {
+            for i in 0..4 {
+                let mut w = 0u32;
+                for (p, &plane) in x[3 - i].iter().enumerate() {
+                    w |= (((plane >> lane) & 1) as u32) << p;
+                }
+                block[i * 4..i * 4 + 4].copy_from_slice(&w.to_be_bytes());
+            }
+        }
+    }
+}
+
+impl From<&SM4> for Sm4Bitsliced {
+    fn from(sm4: &SM4) -> Self {
+        Sm4Bitsliced { rk: sm4.rk }
+    }
+}
+
+impl BlockCipher for Sm4Bitsliced {
+    fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut blocks = [*block];
+        self.crypt_batch(&mut blocks, false);
+        blocks[0]
+    }
+
+    fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut blocks = [*block];
+        self.crypt_batch(&mut blocks, true);
+        blocks[0]
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for chunk in blocks.chunks_mut(LANES) {
+            self.crypt_batch(chunk, false);
+        }
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for chunk in blocks.chunks_mut(LANES) {
+            self.crypt_batch(chunk, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm4::S_TABLE;
+
+    #[test]
+    fn test_sbox_circuit_matches_table() {
+        // 64个分组各取一个输入，4轮覆盖全部256个字节值
+        for base in (0..256).step_by(LANES) {
+            let mut x = [0u64; 8];
+            for lane in 0..LANES {
+                let v = (base + lane) as u8;
+                for (k, plane) in x.iter_mut().enumerate() {
+                    *plane |= (((v >> k) & 1) as u64) << lane;
+                }
+            }
+            let y = sbox(&x);
+            for lane in 0..LANES {
+                let mut v = 0u8;
+                for (k, &plane) in y.iter().enumerate() {
+                    v |= (((plane >> lane) & 1) as u8) << k;
+                }
+                assert_eq!(v, S_TABLE[base + lane], "input {:#04x}", base + lane);
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_schedule_matches_reference() {
+        let key = *b"bitsliced sm4 ks";
+        let reference = crate::sm4::SM4::from_key(&key);
+        assert_eq!(Sm4Bitsliced::new(&key).rk, reference.rk);
+    }
+}
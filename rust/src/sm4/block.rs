@@ -0,0 +1,142 @@
+//! SM4 分组变换的统一接口
+//! - `SM4`：参考实现，逐字节查S盒
+//! - `Sm4TTable`：S盒与线性变换L合并为4张256项u32表，吞吐量优先
+//! - `Sm4Bitsliced`：位切片实现，64个分组并行且不做任何秘密相关的查表，适用于侧信道敏感场景
+
+use super::SM4;
+use super::bitsliced::Sm4Bitsliced;
+use super::ttable::Sm4TTable;
+
+/// SM4 分组变换（不含工作模式与填充），各实现结果完全一致
+pub trait BlockCipher {
+    /// 加密单个16字节分组
+    fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16];
+
+    /// 解密单个16字节分组
+    fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16];
+
+    /// 原地加密多个分组，实现可选择并行处理
+    fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for b in blocks.iter_mut() {
+            *b = self.encrypt_block(b);
+        }
+    }
+
+    /// 原地解密多个分组，实现可选择并行处理
+    fn decrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for b in blocks.iter_mut() {
+            *b = self.decrypt_block(b);
+        }
+    }
+}
+
+impl BlockCipher for SM4 {
+    fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        SM4::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        SM4::decrypt_block(self, block)
+    }
+}
+
+/// 分组变换实现选择
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// 参考实现
+    Reference,
+    /// T表实现
+    TTable,
+    /// 位切片常量时间实现
+    Bitsliced,
+}
+
+/// 按指定实现创建分组变换
+pub fn new_block_cipher(key: &[u8; 16], backend: Backend) -> Box<dyn BlockCipher + Send + Sync> {
+    block_cipher_from(&SM4::from_key(key), backend)
+}
+
+// 复用已扩展的轮密钥创建指定实现
+pub(super) fn block_cipher_from(sm4: &SM4, backend: Backend) -> Box<dyn BlockCipher + Send + Sync> {
+    match backend {
+        Backend::Reference => Box::new(sm4.clone()),
+        Backend::TTable => Box::new(Sm4TTable::from(sm4)),
+        Backend::Bitsliced => Box::new(Sm4Bitsliced::from(sm4)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKENDS: [Backend; 3] = [Backend::Reference, Backend::TTable, Backend::Bitsliced];
+
+    #[test]
+    fn test_standard_vector() {
+        // GB/T 32907 附录A 示例1
+        let key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+            0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+        ];
+        let expected = [
+            0x68, 0x1e, 0xdf, 0x34, 0xd2, 0x06, 0x96, 0x5e,
+            0x86, 0xb3, 0xe9, 0x4f, 0x53, 0x6e, 0x42, 0x46,
+        ];
+        for backend in BACKENDS {
+            let c = new_block_cipher(&key, backend);
+            assert_eq!(c.encrypt_block(&key), expected, "{:?}", backend);
+            assert_eq!(c.decrypt_block(&expected), key, "{:?}", backend);
+        }
+    }
+
+    #[test]
+    fn test_million_iterations_vector() {
+        // GB/T 32907 附录A 示例2：同一密钥迭代加密 1000000 次
+        let key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+            0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+        ];
+        let c = new_block_cipher(&key, Backend::TTable);
+        let mut block = key;
+        for _ in 0..1_000_000 {
+            block = c.encrypt_block(&block);
+        }
+        assert_eq!(
+            block,
+            [
+                0x59, 0x52, 0x98, 0xc7, 0xc6, 0xfd, 0x27, 0x1f,
+                0x04, 0x02, 0xf8, 0x04, 0xc3, 0x3d, 0x3f, 0x66,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backends_agree_on_batches() {
+        let key = *b"0123456789abcdef";
+        // 覆盖不足一批、恰好一批与跨批次的情况
+        for n in [1, 3, 63, 64, 65, 130] {
+            let blocks: Vec<[u8; 16]> = (0..n)
+                .map(|i| {
+                    let mut b = [0u8; 16];
+                    for (j, v) in b.iter_mut().enumerate() {
+                        *v = (i * 17 + j * 29) as u8;
+                    }
+                    b
+                })
+                .collect();
+
+            let reference = new_block_cipher(&key, Backend::Reference);
+            let mut expected = blocks.clone();
+            reference.encrypt_blocks(&mut expected);
+
+            for backend in BACKENDS {
+                let c = new_block_cipher(&key, backend);
+                let mut out = blocks.clone();
+                c.encrypt_blocks(&mut out);
+                assert_eq!(out, expected, "{:?} n={}", backend, n);
+                c.decrypt_blocks(&mut out);
+                assert_eq!(out, blocks, "{:?} n={}", backend, n);
+            }
+        }
+    }
+}
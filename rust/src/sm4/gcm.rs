@@ -0,0 +1,212 @@
+//! SM4-GCM 认证加密（NIST SP 800-38D / RFC 8998）
+//! 标签固定16字节，nonce 推荐12字节
+//! 默认使用位切片常量时间实现，可通过 `with_backend` 选择吞吐量更高的T表实现
+
+use std::sync::Arc;
+
+use super::SM4Error;
+use super::block::{Backend, BlockCipher, new_block_cipher};
+use super::mac::{gcm_j0, gf_mul};
+use crate::util::ct_eq;
+
+/// 认证标签长度（字节）
+pub const TAG_LEN: usize = 16;
+
+/// 单条消息明文上限：2^39 - 256 比特
+const MAX_DATA_LEN: u64 = (1 << 36) - 32;
+
+/// 每批生成的密钥流分组数
+const BATCH: usize = 64;
+
+/// SM4-GCM
+#[derive(Clone)]
+pub struct Sm4Gcm {
+    cipher: Arc<dyn BlockCipher + Send + Sync>,
+    h: u128,
+}
+
+impl Sm4Gcm {
+    /// 使用常量时间的位切片实现（[`Backend::Bitsliced`]）
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self::with_backend(key, Backend::Bitsliced)
+    }
+
+    /// 使用指定的分组变换实现，`TTable` 更快但查表与密钥相关，可能受缓存计时攻击
+    pub fn with_backend(key: &[u8; 16], backend: Backend) -> Self {
+        let cipher: Arc<dyn BlockCipher + Send + Sync> = Arc::from(new_block_cipher(key, backend));
+        let h = u128::from_be_bytes(cipher.encrypt_block(&[0; 16]));
+        Sm4Gcm { cipher, h }
+    }
+
+    /// 原地加密，返回认证标签
+    pub fn encrypt_in_place_detached(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8]) -> Result<[u8; TAG_LEN], SM4Error> {
+        let j0 = self.check(nonce, buf.len())?;
+        self.ctr(&j0, buf);
+        Ok(self.tag(&j0, aad, buf))
+    }
+
+    /// 原地解密，标签校验失败时不解密并返回 `AuthenticationFailed`
+    pub fn decrypt_in_place_detached(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8], tag: &[u8]) -> Result<(), SM4Error> {
+        let j0 = self.check(nonce, buf.len())?;
+        if !ct_eq(&self.tag(&j0, aad, buf), tag) {
+            return Err(SM4Error::AuthenticationFailed);
+        }
+        self.ctr(&j0, buf);
+        Ok(())
+    }
+
+    /// 加密，返回 密文 || 标签
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, SM4Error> {
+        let mut out = plaintext.to_vec();
+        let tag = self.encrypt_in_place_detached(nonce, aad, &mut out)?;
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    /// 解密 密文 || 标签
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], data: &[u8]) -> Result<Vec<u8>, SM4Error> {
+        if data.len() < TAG_LEN {
+            return Err(SM4Error::AuthenticationFailed);
+        }
+        let (ct, tag) = data.split_at(data.len() - TAG_LEN);
+        let mut out = ct.to_vec();
+        self.decrypt_in_place_detached(nonce, aad, &mut out, tag)?;
+        Ok(out)
+    }
+
+    fn check(&self, nonce: &[u8], len: usize) -> Result<[u8; 16], SM4Error> {
+        if nonce.is_empty() {
+            return Err(SM4Error::InvalidIVLength);
+        }
+        if len as u64 > MAX_DATA_LEN {
+            return Err(SM4Error::DataTooLong);
+        }
+        Ok(gcm_j0(self.h, nonce))
+    }
+
+    // GCTR：计数块从 inc32(J0) 开始，只有低32位递增
+    fn ctr(&self, j0: &[u8; 16], data: &mut [u8]) {
+        let prefix = &j0[..12];
+        let mut counter = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]);
+        let mut blocks = [[0u8; 16]; BATCH];
+        for chunk in data.chunks_mut(16 * BATCH) {
+            let n = chunk.len().div_ceil(16);
+            for b in blocks[..n].iter_mut() {
+                counter = counter.wrapping_add(1);
+                b[..12].copy_from_slice(prefix);
+                b[12..].copy_from_slice(&counter.to_be_bytes());
+            }
+            self.cipher.encrypt_blocks(&mut blocks[..n]);
+            for (j, b) in chunk.iter_mut().enumerate() {
+                *b ^= blocks[j / 16][j % 16];
+            }
+        }
+    }
+
+    // GHASH(A || C || len(A) || len(C)) 再与 E(K, J0) 异或
+    fn tag(&self, j0: &[u8; 16], aad: &[u8], ct: &[u8]) -> [u8; TAG_LEN] {
+        let mut acc = 0u128;
+        for data in [aad, ct] {
+            for chunk in data.chunks(16) {
+                let mut block = [0u8; 16];
+                block[..chunk.len()].copy_from_slice(chunk);
+                acc = gf_mul(acc ^ u128::from_be_bytes(block), self.h);
+            }
+        }
+        let len_block = ((aad.len() as u128 * 8) << 64) | (ct.len() as u128 * 8);
+        acc = gf_mul(acc ^ len_block, self.h);
+        let ek = u128::from_be_bytes(self.cipher.encrypt_block(j0));
+        (acc ^ ek).to_be_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn key(s: &str) -> [u8; 16] {
+        unhex(s).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc8998_vector() {
+        // RFC 8998 附录A.1
+        let gcm = Sm4Gcm::new(&key("0123456789ABCDEFFEDCBA9876543210"));
+        let nonce = unhex("00001234567800000000ABCD");
+        let aad = unhex("FEEDFACEDEADBEEFFEEDFACEDEADBEEFABADDAD2");
+        let pt = unhex(
+            "AAAAAAAAAAAAAAAABBBBBBBBBBBBBBBBCCCCCCCCCCCCCCCCDDDDDDDDDDDDDDDD\
+             EEEEEEEEEEEEEEEEFFFFFFFFFFFFFFFFEEEEEEEEEEEEEEEEAAAAAAAAAAAAAAAA",
+        );
+        let expected = unhex(
+            "17F399F08C67D5EE19D0DC9969C4BB7D5FD46FD3756489069157B282BB200735\
+             D82710CA5C22F0CCFA7CBF93D496AC15A56834CBCF98C397B4024A2691233B8D\
+             83DE3541E4C2B58177E065A9BF7B62EC",
+        );
+        let out = gcm.encrypt(&nonce, &aad, &pt).unwrap();
+        assert_eq!(out, expected);
+        assert_eq!(gcm.decrypt(&nonce, &aad, &out).unwrap(), pt);
+
+        for backend in [Backend::Reference, Backend::TTable, Backend::Bitsliced] {
+            let gcm = Sm4Gcm::with_backend(&key("0123456789ABCDEFFEDCBA9876543210"), backend);
+            assert_eq!(gcm.encrypt(&nonce, &aad, &pt).unwrap(), expected, "{:?}", backend);
+        }
+    }
+
+    // 以下期望值由 Python cryptography（OpenSSL）的 SM4-GCM 计算
+    #[test]
+    fn test_vectors() {
+        let gcm = Sm4Gcm::new(&key("000102030405060708090a0b0c0d0e0f"));
+        let cases = [
+            // 非12字节nonce
+            ("000102030405060708090a0b0c0d0e0f101112", "", "54686520717569636b2062726f776e20666f78",
+             "ee39bc4c14d7dd1aefa88caace7e37b85db4707c1bdf51f6ba8d9595d242915e4de71b"),
+            // 只有AAD
+            ("000000000000000000000000", "616263", "", "9fa5a84b5cc247c735729f844c685e5e"),
+            ("000000000000000000000000", "", "", "fad5f2d33a644bfde79e9af64caadbec"),
+        ];
+        for (nonce, aad, pt, expected) in cases {
+            let out = gcm.encrypt(&unhex(nonce), &unhex(aad), &unhex(pt)).unwrap();
+            assert_eq!(out, unhex(expected), "nonce={}", nonce);
+        }
+
+        // 计数器低32位回绕
+        let out = gcm.encrypt(&unhex("ffffffffffffffffffffffff"), b"", &[0u8; 100]).unwrap();
+        assert_eq!(
+            out,
+            unhex(
+                "09651ee8bcfb961d26f38e49e5379af3fa495cc19effc4d391670fb0937b84c6\
+                 91d5ae9a2a4ab52f63eb2d7bc027547916af753afaea0bc491cec9cd1fd46fdd\
+                 557f6a91f138572d7fc138c2db956896d1989c7cebf2d1193c767b8b4f5975ec\
+                 10f0e0da3183807808143f5a47e3381ba491be48"
+            )
+        );
+    }
+
+    #[test]
+    fn test_tamper_detection() {
+        let gcm = Sm4Gcm::new(b"0123456789abcdef");
+        let nonce = [7u8; 12];
+        let data: Vec<u8> = (0..5000).map(|i| i as u8).collect();
+        let out = gcm.encrypt(&nonce, b"header", &data).unwrap();
+        assert_eq!(gcm.decrypt(&nonce, b"header", &out).unwrap(), data);
+
+        let mut bad = out.clone();
+        bad[10] ^= 1;
+        assert_eq!(gcm.decrypt(&nonce, b"header", &bad), Err(SM4Error::AuthenticationFailed));
+        assert_eq!(gcm.decrypt(&nonce, b"other", &out), Err(SM4Error::AuthenticationFailed));
+        assert_eq!(gcm.decrypt(&[8u8; 12], b"header", &out), Err(SM4Error::AuthenticationFailed));
+        assert_eq!(gcm.decrypt(&nonce, b"header", &out[..15]), Err(SM4Error::AuthenticationFailed));
+        assert_eq!(gcm.encrypt(&[], b"", b"x"), Err(SM4Error::InvalidIVLength));
+
+        // 校验失败时缓冲区保持密文不变
+        let mut buf = out[..out.len() - TAG_LEN].to_vec();
+        let before = buf.clone();
+        assert!(gcm.decrypt_in_place_detached(&nonce, b"", &mut buf, &out[out.len() - TAG_LEN..]).is_err());
+        assert_eq!(buf, before);
+    }
+}
@@ -0,0 +1,403 @@
+//! 基于SM4的消息认证码
+//! - CBC-MAC: GB/T 15852.1 (ISO/IEC 9797-1) MAC算法1与算法3
+//! - CMAC: NIST SP 800-38B (OMAC1)
+//! - GMAC: NIST SP 800-38D (仅认证数据的GCM)
+
+use super::{SM4, SM4Error};
+use crate::util::ct_eq;
+
+/// 截断MAC允许的最短长度（字节）
+const MIN_TAG_LEN: usize = 4;
+
+// 校验可能被截断的MAC（取最左侧字节）
+fn verify_tag(full: &[u8; 16], tag: &[u8]) -> bool {
+    if tag.len() < MIN_TAG_LEN || tag.len() > 16 {
+        return false;
+    }
+    ct_eq(&full[..tag.len()], tag)
+}
+
+fn xor_block(a: &mut [u8; 16], b: &[u8; 16]) {
+    for i in 0..16 {
+        a[i] ^= b[i];
+    }
+}
+
+/// ISO/IEC 9797-1 填充方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CbcMacPadding {
+    /// 方法1：补0至分组长度（空消息补一个全0分组）
+    Method1,
+    /// 方法2：先补0x80，再补0至分组长度
+    Method2,
+}
+
+/// SM4 CBC-MAC（ISO/IEC 9797-1 MAC算法1 / 算法3）
+pub struct CbcMac {
+    cipher: SM4,
+    // 算法3的第二密钥K'，算法1为None
+    cipher2: Option<SM4>,
+    padding: CbcMacPadding,
+    state: [u8; 16],
+    buff: [u8; 16],
+    buff_len: usize,
+    empty: bool,
+}
+
+impl CbcMac {
+    /// MAC算法1：单密钥CBC-MAC
+    pub fn new(key: &[u8; 16], padding: CbcMacPadding) -> Self {
+        CbcMac {
+            cipher: SM4::from_key(key),
+            cipher2: None,
+            padding,
+            state: [0; 16],
+            buff: [0; 16],
+            buff_len: 0,
+            empty: true,
+        }
+    }
+
+    /// MAC算法3（Retail MAC）：末块输出变换为 E_K(D_K'(H))
+    pub fn new_alg3(key: &[u8; 16], key2: &[u8; 16], padding: CbcMacPadding) -> Self {
+        let mut mac = Self::new(key, padding);
+        mac.cipher2 = Some(SM4::from_key(key2));
+        mac
+    }
+
+    /// 更新字节数组
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        if !data.is_empty() {
+            self.empty = false;
+        }
+        for &b in data {
+            self.buff[self.buff_len] = b;
+            self.buff_len += 1;
+            if self.buff_len == 16 {
+                self.process_buff();
+            }
+        }
+        self
+    }
+
+    fn process_buff(&mut self) {
+        xor_block(&mut self.state, &self.buff);
+        self.state = self.cipher.encrypt_block(&self.state);
+        self.buff_len = 0;
+    }
+
+    /// 计算MAC
+    pub fn finalize(mut self) -> [u8; 16] {
+        match self.padding {
+            CbcMacPadding::Method1 => {
+                if self.buff_len > 0 || self.empty {
+                    self.buff[self.buff_len..].fill(0);
+                    self.process_buff();
+                }
+            }
+            CbcMacPadding::Method2 => {
+                self.buff[self.buff_len] = 0x80;
+                self.buff[self.buff_len + 1..].fill(0);
+                self.process_buff();
+            }
+        }
+
+        match &self.cipher2 {
+            Some(c2) => self.cipher.encrypt_block(&c2.decrypt_block(&self.state)),
+            None => self.state,
+        }
+    }
+
+    /// 常量时间校验MAC，允许截断（不少于4字节）
+    pub fn verify(self, tag: &[u8]) -> bool {
+        verify_tag(&self.finalize(), tag)
+    }
+}
+
+/// SM4-CMAC（OMAC1）
+pub struct Cmac {
+    cipher: SM4,
+    k1: [u8; 16],
+    k2: [u8; 16],
+    state: [u8; 16],
+    buff: [u8; 16],
+    buff_len: usize,
+}
+
+impl Cmac {
+    pub fn new(key: &[u8; 16]) -> Self {
+        let cipher = SM4::from_key(key);
+        let l = cipher.encrypt_block(&[0; 16]);
+        let k1 = Self::dbl(&l);
+        let k2 = Self::dbl(&k1);
+        Cmac {
+            cipher,
+            k1,
+            k2,
+            state: [0; 16],
+            buff: [0; 16],
+            buff_len: 0,
+        }
+    }
+
+    // GF(2^128)上乘x，Rb = 0x87
+    fn dbl(b: &[u8; 16]) -> [u8; 16] {
+        let v = u128::from_be_bytes(*b);
+        let mask = 0u128.wrapping_sub(v >> 127);
+        ((v << 1) ^ (mask & 0x87)).to_be_bytes()
+    }
+
+    /// 更新字节数组
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        for &b in data {
+            // 最后一个分组需特殊处理，缓冲区满时延迟到有后续数据再处理
+            if self.buff_len == 16 {
+                xor_block(&mut self.state, &self.buff);
+                self.state = self.cipher.encrypt_block(&self.state);
+                self.buff_len = 0;
+            }
+            self.buff[self.buff_len] = b;
+            self.buff_len += 1;
+        }
+        self
+    }
+
+    /// 计算MAC
+    pub fn finalize(mut self) -> [u8; 16] {
+        let mut last = [0u8; 16];
+        if self.buff_len == 16 {
+            last = self.buff;
+            xor_block(&mut last, &self.k1);
+        } else {
+            last[..self.buff_len].copy_from_slice(&self.buff[..self.buff_len]);
+            last[self.buff_len] = 0x80;
+            xor_block(&mut last, &self.k2);
+        }
+        xor_block(&mut self.state, &last);
+        self.cipher.encrypt_block(&self.state)
+    }
+
+    /// 常量时间校验MAC，允许截断（不少于4字节）
+    pub fn verify(self, tag: &[u8]) -> bool {
+        verify_tag(&self.finalize(), tag)
+    }
+}
+
+/// SM4-GMAC
+pub struct Gmac {
+    cipher: SM4,
+    h: u128,
+    j0: [u8; 16],
+    acc: u128,
+    buff: [u8; 16],
+    buff_len: usize,
+    aad_len: u64,
+}
+
+impl Gmac {
+    /// 创建GMAC，IV推荐12字节；IV不能为空
+    pub fn new(key: &[u8; 16], iv: &[u8]) -> Result<Self, SM4Error> {
+        if iv.is_empty() {
+            return Err(SM4Error::InvalidIVLength);
+        }
+        let cipher = SM4::from_key(key);
+        let h = u128::from_be_bytes(cipher.encrypt_block(&[0; 16]));
+
+        let j0 = gcm_j0(h, iv);
+
+        Ok(Gmac {
+            cipher,
+            h,
+            j0,
+            acc: 0,
+            buff: [0; 16],
+            buff_len: 0,
+            aad_len: 0,
+        })
+    }
+
+    /// 更新附加认证数据
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.aad_len += data.len() as u64;
+        for &b in data {
+            self.buff[self.buff_len] = b;
+            self.buff_len += 1;
+            if self.buff_len == 16 {
+                self.acc = gf_mul(self.acc ^ u128::from_be_bytes(self.buff), self.h);
+                self.buff_len = 0;
+            }
+        }
+        self
+    }
+
+    /// 计算MAC
+    pub fn finalize(mut self) -> [u8; 16] {
+        if self.buff_len > 0 {
+            self.buff[self.buff_len..].fill(0);
+            self.acc = gf_mul(self.acc ^ u128::from_be_bytes(self.buff), self.h);
+        }
+        // len(A) || len(C)，密文长度为0
+        let len_block = (self.aad_len as u128 * 8) << 64;
+        let s = gf_mul(self.acc ^ len_block, self.h);
+        let ek = u128::from_be_bytes(self.cipher.encrypt_block(&self.j0));
+        (s ^ ek).to_be_bytes()
+    }
+
+    /// 常量时间校验MAC，允许截断（不少于4字节）
+    pub fn verify(self, tag: &[u8]) -> bool {
+        verify_tag(&self.finalize(), tag)
+    }
+}
+
+// 预计数块 J0：12字节IV直接拼接计数1，否则对IV做GHASH
+pub(super) fn gcm_j0(h: u128, iv: &[u8]) -> [u8; 16] {
+    if iv.len() == 12 {
+        let mut j0 = [0u8; 16];
+        j0[..12].copy_from_slice(iv);
+        j0[15] = 1;
+        j0
+    } else {
+        let mut acc = 0u128;
+        for chunk in iv.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            acc = gf_mul(acc ^ u128::from_be_bytes(block), h);
+        }
+        acc = gf_mul(acc ^ (iv.len() as u128 * 8), h);
+        acc.to_be_bytes()
+    }
+}
+
+// GF(2^128)乘法（GCM位序），逐位掩码实现，不依赖秘密数据分支
+pub(super) fn gf_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    let mut z = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & 0u128.wrapping_sub(bit);
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(lsb));
+    }
+    z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 16] = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+        0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+    ];
+    const KEY2: [u8; 16] = [
+        0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+    ];
+    const MSG: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // 以下期望值均由 OpenSSL 3 (SM4-CBC/SM4-GCM) 计算得到
+    #[test]
+    fn test_cbc_mac_alg1() {
+        let mut mac = CbcMac::new(&KEY, CbcMacPadding::Method1);
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "d104e5c08198a5a4b88801aab435cd85");
+
+        let mut mac = CbcMac::new(&KEY, CbcMacPadding::Method2);
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "4be4fe8cb829a00236c48d9f00279ba9");
+    }
+
+    #[test]
+    fn test_cbc_mac_alg3() {
+        let mut mac = CbcMac::new_alg3(&KEY, &KEY2, CbcMacPadding::Method1);
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "a74b0eec532f4dc70fb33b2ffcf24efe");
+
+        let mut mac = CbcMac::new_alg3(&KEY, &KEY2, CbcMacPadding::Method2);
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "d4476cd490ba577a7d7a6c44c96239af");
+    }
+
+    #[test]
+    fn test_cmac() {
+        let mut mac = Cmac::new(&KEY);
+        mac.update(b"abc");
+        assert_eq!(hex(&mac.finalize()), "8e75238ac2672a6aee408c1e251854d8");
+
+        let mac = Cmac::new(&KEY);
+        assert_eq!(hex(&mac.finalize()), "29e154322e5c7bd8ee6a25ba549b24bc");
+
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "f41bfe3d65ccd8ea6f0f129d0ea36fc7");
+    }
+
+    #[test]
+    fn test_gmac() {
+        let iv: Vec<u8> = (0u8..12).collect();
+        let mut mac = Gmac::new(&KEY, &iv).unwrap();
+        mac.update(b"abc");
+        assert_eq!(hex(&mac.finalize()), "3a49c13ebcb7f0c6278ed19b5307d45f");
+
+        let mut mac = Gmac::new(&KEY, &iv).unwrap();
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "3afe1f6631ab4cdfa4f4de6835e440f8");
+
+        // 非12字节IV
+        let iv: Vec<u8> = (0u8..16).collect();
+        let mut mac = Gmac::new(&KEY, &iv).unwrap();
+        mac.update(MSG);
+        assert_eq!(hex(&mac.finalize()), "268a93b739a3b7bf832476af6de52ebd");
+
+        assert!(Gmac::new(&KEY, &[]).is_err());
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let mut one = Cmac::new(&KEY);
+        one.update(MSG);
+        let mut parts = Cmac::new(&KEY);
+        for chunk in MSG.chunks(5) {
+            parts.update(chunk);
+        }
+        assert_eq!(one.finalize(), parts.finalize());
+
+        let mut one = CbcMac::new(&KEY, CbcMacPadding::Method2);
+        one.update(&MSG[..32]);
+        let mut parts = CbcMac::new(&KEY, CbcMacPadding::Method2);
+        parts.update(&MSG[..16]).update(&MSG[16..32]);
+        assert_eq!(one.finalize(), parts.finalize());
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        let tag = mac.finalize();
+
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        assert!(mac.verify(&tag));
+
+        // 截断到8字节
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        assert!(mac.verify(&tag[..8]));
+
+        let mut bad = tag;
+        bad[0] ^= 1;
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        assert!(!mac.verify(&bad));
+
+        // 过短的截断值拒绝
+        let mut mac = Cmac::new(&KEY);
+        mac.update(MSG);
+        assert!(!mac.verify(&tag[..2]));
+    }
+}
@@ -0,0 +1,402 @@
+//! SM4 增量加解密（CBC/CTR）与 `std::io` 适配器
+//! 以固定内存处理任意长度数据，结果与 `SM4::encrypt_with_iv`/`SM4::decrypt_with_iv` 一致
+
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use super::block::{Backend, BlockCipher, block_cipher_from};
+use super::{SM4, SM4Error, parallel};
+
+/// 增量加解密上下文
+pub trait CipherContext {
+    /// 处理一段输入，返回当前可输出的数据（可能为空）
+    fn update(&mut self, data: &[u8]) -> Vec<u8>;
+
+    /// 处理最后的填充分组并返回剩余输出，消耗上下文
+    fn finalize(self) -> Result<Vec<u8>, SM4Error>;
+}
+
+/// CBC模式增量加密（PKCS#7填充）
+#[derive(Clone)]
+pub struct CbcEncryptor {
+    cipher: SM4,
+    iv: [u8; 16],
+    buff: [u8; 16],
+    buff_len: usize,
+}
+
+impl CbcEncryptor {
+    pub fn new(cipher: &SM4, iv: &[u8; 16]) -> Self {
+        CbcEncryptor {
+            cipher: cipher.clone(),
+            iv: *iv,
+            buff: [0; 16],
+            buff_len: 0,
+        }
+    }
+
+    fn encrypt_buff(&mut self, out: &mut Vec<u8>) {
+        for i in 0..16 {
+            self.buff[i] ^= self.iv[i];
+        }
+        self.iv = self.cipher.encrypt_block(&self.buff);
+        out.extend_from_slice(&self.iv);
+        self.buff_len = 0;
+    }
+}
+
+impl CipherContext for CbcEncryptor {
+    fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity((self.buff_len + data.len()) / 16 * 16);
+        for &b in data {
+            self.buff[self.buff_len] = b;
+            self.buff_len += 1;
+            if self.buff_len == 16 {
+                self.encrypt_buff(&mut out);
+            }
+        }
+        out
+    }
+
+    fn finalize(mut self) -> Result<Vec<u8>, SM4Error> {
+        let pad = (16 - self.buff_len) as u8;
+        self.buff[self.buff_len..].fill(pad);
+        let mut out = Vec::with_capacity(16);
+        self.encrypt_buff(&mut out);
+        Ok(out)
+    }
+}
+
+/// CBC模式增量解密（去除PKCS#7填充）
+#[derive(Clone)]
+pub struct CbcDecryptor {
+    cipher: SM4,
+    iv: [u8; 16],
+    buff: [u8; 16],
+    buff_len: usize,
+    // 缓冲区已满的最后一个分组需留到 finalize 时去除填充
+    pending: Option<[u8; 16]>,
+}
+
+impl CbcDecryptor {
+    pub fn new(cipher: &SM4, iv: &[u8; 16]) -> Self {
+        CbcDecryptor {
+            cipher: cipher.clone(),
+            iv: *iv,
+            buff: [0; 16],
+            buff_len: 0,
+            pending: None,
+        }
+    }
+
+    fn decrypt_block(&mut self, block: &[u8; 16]) -> [u8; 16] {
+        let mut plain = self.cipher.decrypt_block(block);
+        for (p, v) in plain.iter_mut().zip(&self.iv) {
+            *p ^= v;
+        }
+        self.iv = *block;
+        plain
+    }
+}
+
+impl CipherContext for CbcDecryptor {
+    fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity((self.buff_len + data.len()) / 16 * 16);
+        for &b in data {
+            self.buff[self.buff_len] = b;
+            self.buff_len += 1;
+            if self.buff_len == 16 {
+                if let Some(prev) = self.pending.take() {
+                    out.extend_from_slice(&self.decrypt_block(&prev));
+                }
+                self.pending = Some(self.buff);
+                self.buff_len = 0;
+            }
+        }
+        out
+    }
+
+    fn finalize(mut self) -> Result<Vec<u8>, SM4Error> {
+        if self.buff_len != 0 {
+            return Err(SM4Error::InvalidDataLength);
+        }
+        match self.pending.take() {
+            Some(last) => {
+                let plain = self.decrypt_block(&last);
+                self.cipher.pkcs7_unpad(&plain)
+            }
+            // 与 decrypt_with_iv 一致，空密文解密为空明文
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// CTR模式增量加解密（加密与解密相同，无填充）
+#[derive(Clone)]
+pub struct CtrCipher {
+    cipher: Arc<dyn BlockCipher + Send + Sync>,
+    counter: [u8; 16],
+    keystream: [u8; 16],
+    used: usize,
+}
+
+impl CtrCipher {
+    /// 使用常量时间的位切片实现（[`Backend::Bitsliced`]）
+    pub fn new(cipher: &SM4, counter: &[u8; 16]) -> Self {
+        Self::with_backend(cipher, counter, Backend::Bitsliced)
+    }
+
+    /// 使用指定的分组变换实现，`TTable` 更快但查表与密钥相关
+    pub fn with_backend(cipher: &SM4, counter: &[u8; 16], backend: Backend) -> Self {
+        CtrCipher {
+            cipher: Arc::from(block_cipher_from(cipher, backend)),
+            counter: *counter,
+            keystream: [0; 16],
+            used: 16,
+        }
+    }
+}
+
+impl CipherContext for CtrCipher {
+    fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = data.to_vec();
+        let mut pos = 0;
+
+        // 先用完上次剩余的密钥流
+        while self.used < 16 && pos < out.len() {
+            out[pos] ^= self.keystream[self.used];
+            self.used += 1;
+            pos += 1;
+        }
+
+        // 整分组部分按批并行处理
+        let full = (out.len() - pos) / 16 * 16;
+        parallel::ctr_apply_keystream(&*self.cipher, &self.counter, &mut out[pos..pos + full]);
+        self.counter = (u128::from_be_bytes(self.counter).wrapping_add((full / 16) as u128)).to_be_bytes();
+        pos += full;
+
+        if pos < out.len() {
+            self.keystream = self.cipher.encrypt_block(&self.counter);
+            self.counter = (u128::from_be_bytes(self.counter).wrapping_add(1)).to_be_bytes();
+            self.used = 0;
+            while pos < out.len() {
+                out[pos] ^= self.keystream[self.used];
+                self.used += 1;
+                pos += 1;
+            }
+        }
+        out
+    }
+
+    fn finalize(self) -> Result<Vec<u8>, SM4Error> {
+        Ok(Vec::new())
+    }
+}
+
+fn to_io_error(e: SM4Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// 写入适配器：写入的数据经加解密后写入内部 `Write`
+///
+/// 写完后必须调用 `finish` 输出最后的填充分组并取得错误（如解密时填充无效）。
+/// 未调用 `finish` 而直接 drop 时不会输出最后的分组，输出保持未结束状态，
+/// 这样中途出错时截断的密文无法被当作完整密文解密
+pub struct CipherWriter<W: Write, C: CipherContext> {
+    inner: W,
+    ctx: C,
+}
+
+impl<W: Write, C: CipherContext> CipherWriter<W, C> {
+    pub fn new(inner: W, ctx: C) -> Self {
+        CipherWriter { inner, ctx }
+    }
+
+    /// 输出最后的分组并返回内部 `Write`
+    pub fn finish(self) -> io::Result<W> {
+        let CipherWriter { mut inner, ctx } = self;
+        inner.write_all(&ctx.finalize().map_err(to_io_error)?)?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write, C: CipherContext> Write for CipherWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let out = self.ctx.update(buf);
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 读取适配器：从内部 `Read` 读取的数据经加解密后返回
+pub struct CipherReader<R: Read, C: CipherContext> {
+    inner: R,
+    // 读到末尾并 finalize 后为 None
+    ctx: Option<C>,
+    out: Vec<u8>,
+    pos: usize,
+}
+
+// 读取适配器每次从内部 `Read` 读取的字节数
+const READ_CHUNK: usize = 8192;
+
+impl<R: Read, C: CipherContext> CipherReader<R, C> {
+    pub fn new(inner: R, ctx: C) -> Self {
+        CipherReader {
+            inner,
+            ctx: Some(ctx),
+            out: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// 返回内部 `Read`
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, C: CipherContext> Read for CipherReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0u8; READ_CHUNK];
+        while self.pos == self.out.len() {
+            let Some(ctx) = self.ctx.as_mut() else {
+                return Ok(0);
+            };
+            let n = self.inner.read(&mut chunk)?;
+            self.out = if n == 0 {
+                self.ctx.take().unwrap().finalize().map_err(to_io_error)?
+            } else {
+                ctx.update(&chunk[..n])
+            };
+            self.pos = 0;
+        }
+
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8; 16] = b"0123456789abcdef";
+    const IV: [u8; 16] = [7; 16];
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    #[test]
+    fn test_update_matches_one_shot() {
+        let sm4 = SM4::with_key(KEY).unwrap();
+        for len in [0, 1, 15, 16, 17, 100, 4096] {
+            let data = sample(len);
+            let expected = sm4.encrypt_with_iv(&data, &IV);
+
+            let mut enc = CbcEncryptor::new(&sm4, &IV);
+            let mut ct = Vec::new();
+            for chunk in data.chunks(7) {
+                ct.extend(enc.update(chunk));
+            }
+            ct.extend(enc.finalize().unwrap());
+            assert_eq!(ct, expected);
+
+            let mut dec = CbcDecryptor::new(&sm4, &IV);
+            let mut pt = Vec::new();
+            for chunk in ct.chunks(5) {
+                pt.extend(dec.update(chunk));
+            }
+            pt.extend(dec.finalize().unwrap());
+            assert_eq!(pt, data);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let sm4 = SM4::with_key(KEY).unwrap();
+        let ct = sm4.encrypt_with_iv(b"hello", &IV);
+
+        let mut dec = CbcDecryptor::new(&sm4, &IV);
+        dec.update(&ct[..10]);
+        assert!(matches!(dec.finalize(), Err(SM4Error::InvalidDataLength)));
+
+        // 错误的密钥导致填充校验失败
+        let other = SM4::with_key(b"fedcba9876543210").unwrap();
+        let mut dec = CbcDecryptor::new(&other, &IV);
+        dec.update(&ct);
+        assert!(dec.finalize().is_err());
+    }
+
+    #[test]
+    fn test_ctr_update_matches_one_shot() {
+        let sm4 = SM4::with_key(KEY).unwrap();
+        let data = sample(5000);
+        let mut expected = data.clone();
+        parallel::ctr_apply_keystream(&sm4, &IV, &mut expected);
+
+        for backend in [Backend::Reference, Backend::TTable, Backend::Bitsliced] {
+            for step in [1, 7, 16, 33, 1024] {
+                let mut ctx = CtrCipher::with_backend(&sm4, &IV, backend);
+                let mut ct = Vec::new();
+                for chunk in data.chunks(step) {
+                    ct.extend(ctx.update(chunk));
+                }
+                ct.extend(ctx.finalize().unwrap());
+                assert_eq!(ct, expected, "{:?} step={}", backend, step);
+            }
+        }
+
+        let mut reader = CipherReader::new(expected.as_slice(), CtrCipher::new(&sm4, &IV));
+        let mut pt = Vec::new();
+        reader.read_to_end(&mut pt).unwrap();
+        assert_eq!(pt, data);
+    }
+
+    #[test]
+    fn test_io_adapters() {
+        let sm4 = SM4::with_key(KEY).unwrap();
+        let data = sample(100_000);
+
+        let mut writer = CipherWriter::new(Vec::new(), CbcEncryptor::new(&sm4, &IV));
+        io::copy(&mut data.as_slice(), &mut writer).unwrap();
+        let ct = writer.finish().unwrap();
+        assert_eq!(ct, sm4.encrypt_with_iv(&data, &IV));
+
+        let mut reader = CipherReader::new(ct.as_slice(), CbcDecryptor::new(&sm4, &IV));
+        let mut pt = Vec::new();
+        reader.read_to_end(&mut pt).unwrap();
+        assert_eq!(pt, data);
+
+        // 加密读取 + 解密写入
+        let mut reader = CipherReader::new(data.as_slice(), CbcEncryptor::new(&sm4, &IV));
+        let mut writer = CipherWriter::new(Vec::new(), CbcDecryptor::new(&sm4, &IV));
+        io::copy(&mut reader, &mut writer).unwrap();
+        assert_eq!(writer.finish().unwrap(), data);
+
+        // 未调用 finish 时只输出已满的分组，不补填充分组
+        let mut ct = Vec::new();
+        {
+            let mut writer = CipherWriter::new(&mut ct, CbcEncryptor::new(&sm4, &IV));
+            writer.write_all(b"dropped without finish").unwrap();
+        }
+        assert_eq!(ct, sm4.encrypt_with_iv(b"dropped without finish", &IV)[..16]);
+    }
+
+    #[test]
+    fn test_reader_reports_invalid_data() {
+        let sm4 = SM4::with_key(KEY).unwrap();
+        let ct = sm4.encrypt_with_iv(b"hello world", &IV);
+        let mut reader = CipherReader::new(&ct[..ct.len() - 1], CbcDecryptor::new(&sm4, &IV));
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
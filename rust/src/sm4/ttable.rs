@@ -0,0 +1,138 @@
+//! SM4 T表实现：T_i[x] = L(S(x) << (24 - 8i))，轮函数只需4次查表与异或
+
+use super::block::BlockCipher;
+use super::{S_TABLE, SM4};
+
+const fn l(b: u32) -> u32 {
+    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+}
+
+const fn build_table(shift: u32) -> [u32; 256] {
+    let mut t = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        t[i] = l((S_TABLE[i] as u32) << shift);
+        i += 1;
+    }
+    t
+}
+
+// 编译期生成的4张合并表
+static T0: [u32; 256] = build_table(24);
+static T1: [u32; 256] = build_table(16);
+static T2: [u32; 256] = build_table(8);
+static T3: [u32; 256] = build_table(0);
+
+/// SM4 T表实现
+#[derive(Clone)]
+pub struct Sm4TTable {
+    rk: [u32; 32],
+}
+
+impl Sm4TTable {
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self::from(&SM4::from_key(key))
+    }
+
+    #[inline(always)]
+    fn t(a: u32) -> u32 {
+        T0[(a >> 24) as usize]
+            ^ T1[((a >> 16) & 0xFF) as usize]
+            ^ T2[((a >> 8) & 0xFF) as usize]
+            ^ T3[(a & 0xFF) as usize]
+    }
+
+    fn crypt(&self, block: &[u8; 16], decrypt: bool) -> [u8; 16] {
+        let mut x0 = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+        let mut x1 = u32::from_be_bytes([block[4], block[5], block[6], block[7]]);
+        let mut x2 = u32::from_be_bytes([block[8], block[9], block[10], block[11]]);
+        let mut x3 = u32::from_be_bytes([block[12], block[13], block[14], block[15]]);
+
+        // 每次迭代展开4轮，省去状态字轮换
+        for i in (0..32).step_by(4) {
+            let rk = |j: usize| if decrypt { self.rk[31 - j] } else { self.rk[j] };
+            x0 ^= Self::t(x1 ^ x2 ^ x3 ^ rk(i));
+            x1 ^= Self::t(x2 ^ x3 ^ x0 ^ rk(i + 1));
+            x2 ^= Self::t(x3 ^ x0 ^ x1 ^ rk(i + 2));
+            x3 ^= Self::t(x0 ^ x1 ^ x2 ^ rk(i + 3));
+        }
+
+        // 反序变换 R
+        let mut out = [0u8; 16];
+        out[..4].copy_from_slice(&x3.to_be_bytes());
+        out[4..8].copy_from_slice(&x2.to_be_bytes());
+        out[8..12].copy_from_slice(&x1.to_be_bytes());
+        out[12..].copy_from_slice(&x0.to_be_bytes());
+        out
+    }
+
+    // 4个分组交错计算，各分组的查表互不依赖，便于CPU流水线并行
+    fn crypt4(&self, blocks: &mut [[u8; 16]; 4], decrypt: bool) {
+        let mut x = [[0u32; 4]; 4];
+        for (b, block) in blocks.iter().enumerate() {
+            for i in 0..4 {
+                x[i][b] = u32::from_be_bytes([block[i * 4], block[i * 4 + 1], block[i * 4 + 2], block[i * 4 + 3]]);
+            }
+        }
+        let [mut x0, mut x1, mut x2, mut x3] = x;
+
+        for i in (0..32).step_by(4) {
+            let rk = |j: usize| if decrypt { self.rk[31 - j] } else { self.rk[j] };
+            for b in 0..4 {
+                x0[b] ^= Self::t(x1[b] ^ x2[b] ^ x3[b] ^ rk(i));
+            }
+            for b in 0..4 {
+                x1[b] ^= Self::t(x2[b] ^ x3[b] ^ x0[b] ^ rk(i + 1));
+            }
+            for b in 0..4 {
+                x2[b] ^= Self::t(x3[b] ^ x0[b] ^ x1[b] ^ rk(i + 2));
+            }
+            for b in 0..4 {
+                x3[b] ^= Self::t(x0[b] ^ x1[b] ^ x2[b] ^ rk(i + 3));
+            }
+        }
+
+        // 反序变换 R
+        let x = [x3, x2, x1, x0];
+        for (b, block) in blocks.iter_mut().enumerate() {
+            for i in 0..4 {
+                block[i * 4..i * 4 + 4].copy_from_slice(&x[i][b].to_be_bytes());
+            }
+        }
+    }
+
+    fn crypt_blocks(&self, blocks: &mut [[u8; 16]], decrypt: bool) {
+        let mut chunks = blocks.chunks_exact_mut(4);
+        for chunk in &mut chunks {
+            let chunk: &mut [[u8; 16]; 4] = chunk.try_into().unwrap();
+            self.crypt4(chunk, decrypt);
+        }
+        for b in chunks.into_remainder() {
+            *b = self.crypt(b, decrypt);
+        }
+    }
+}
+
+impl From<&SM4> for Sm4TTable {
+    fn from(sm4: &SM4) -> Self {
+        Sm4TTable { rk: sm4.rk }
+    }
+}
+
+impl BlockCipher for Sm4TTable {
+    fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        self.crypt(block, false)
+    }
+
+    fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        self.crypt(block, true)
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        self.crypt_blocks(blocks, false);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        self.crypt_blocks(blocks, true);
+    }
+}
@@ -0,0 +1,133 @@
+// crate 内部共享的辅助函数
+
+use std::io;
+
+/// 常量时间比较两个字节串（长度不同直接返回 false，长度本身不视为秘密）
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for i in 0..a.len() {
+        diff |= a[i] ^ b[i];
+    }
+    // 阻止编译器将累积结果短路优化
+    core::hint::black_box(diff) == 0
+}
+
+/// 使用系统随机数生成器填充字节数组
+///
+/// 没有可用的安全随机源时返回错误（如 `/dev/urandom` 无法读取，或 WASM 等没有系统熵源的平台），
+/// 此时 `dest` 的内容不可使用
+pub(crate) fn getrandom(dest: &mut [u8]) -> io::Result<()> {
+    #[cfg(target_family = "unix")]
+    {
+        use std::fs::File;
+        use std::io::Read;
+
+        File::open("/dev/urandom")?.read_exact(dest)
+    }
+
+    #[cfg(target_family = "windows")]
+    {
+        #[link(name = "bcrypt")]
+        unsafe extern "system" {
+            fn BCryptGenRandom(
+                hAlgorithm: *mut core::ffi::c_void,
+                pbBuffer: *mut u8,
+                cbBuffer: u32,
+                dwFlags: u32,
+            ) -> i32;
+        }
+
+        for chunk in dest.chunks_mut(u32::MAX as usize) {
+            // SAFETY: 指针与长度来自同一个可写切片，BCRYPT_USE_SYSTEM_PREFERRED_RNG 不需要算法句柄
+            let status = unsafe {
+                BCryptGenRandom(
+                    core::ptr::null_mut(),
+                    chunk.as_mut_ptr(),
+                    chunk.len() as u32,
+                    2, // BCRYPT_USE_SYSTEM_PREFERRED_RNG
+                )
+            };
+            // NTSTATUS 为负表示失败
+            if status < 0 {
+                return Err(io::Error::other(format!("BCryptGenRandom failed: {:#x}", status)));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_family = "unix", target_family = "windows")))]
+    {
+        let _ = dest;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "no secure random number generator on this platform"))
+    }
+}
+
+const B64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// 标准Base64编码，`pad` 为 false 时省略末尾的 '='
+pub(crate) fn base64_encode(data: &[u8], pad: bool) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        let chars = chunk.len() + 1;
+        for i in 0..4 {
+            if i < chars {
+                out.push(B64_ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else if pad {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// 标准Base64解码，末尾的 '=' 可有可无，其余非法字符返回 None
+pub(crate) fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim_end_matches('=').as_bytes();
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let v = B64_ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= v << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"abc", b"abc"));
+        assert!(!ct_eq(b"abc", b"abd"));
+        assert!(!ct_eq(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn test_base64() {
+        let cases: [(&[u8], &str); 4] = [(b"", ""), (b"f", "Zg=="), (b"fo", "Zm8="), (b"foobar", "Zm9vYmFy")];
+        for (raw, enc) in cases {
+            assert_eq!(base64_encode(raw, true), enc);
+            assert_eq!(base64_encode(raw, false), enc.trim_end_matches('='));
+            assert_eq!(base64_decode(enc).unwrap(), raw);
+            assert_eq!(base64_decode(enc.trim_end_matches('=')).unwrap(), raw);
+        }
+        assert!(base64_decode("Zm9v!").is_none());
+        assert!(base64_decode("Z").is_none());
+    }
+}
@@ -0,0 +1,490 @@
+//! SM2 X.509 v3 证书（RFC 5280，GM/T 0015）
+//!
+//! 只支持 SM2 公钥与 SM2-with-SM3 (1.2.156.10197.1.501) 签名，验签使用默认ID `1234567812345678`。
+//! 证书路径的构建与验证见 [`TrustStore`]，签发证书见 [`CertificateBuilder`] 与 [`CsrBuilder`]，
+//! 撤销检查见 [`CertificateRevocationList`]。
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::time::SystemTime;
+
+use crate::der::{self, DerError, Reader, oid};
+use crate::pem;
+use crate::sm2::{SM2Error, Signature, VerifyingKey};
+use crate::sm3::Sm3;
+
+mod builder;
+mod chain;
+mod crl;
+mod csr;
+mod ext;
+mod name;
+pub(crate) mod time;
+
+pub use builder::CertificateBuilder;
+pub use chain::TrustStore;
+pub use crl::{CertificateRevocationList, RevocationReason, RevokedCertificate};
+pub use csr::{CertificateRequest, CsrBuilder};
+pub use ext::{BasicConstraints, Extension, GeneralName, KeyUsage, eku};
+pub use name::{Name, attr};
+
+const CERTIFICATE_LABEL: &str = "CERTIFICATE";
+
+/// 证书相关错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum X509Error {
+    InvalidEncoding,
+    UnsupportedAlgorithm,
+    InvalidSignature,
+    NotYetValid,
+    Expired,
+    UnknownIssuer,
+    NotCa,
+    PathLengthExceeded,
+    UnsupportedCriticalExtension,
+    KeyMismatch,
+    /// 序列号不是正整数或编码超过20字节
+    InvalidSerialNumber,
+    /// 有效期结束时间早于开始时间
+    InvalidValidity,
+    /// 系统随机数生成器不可用
+    RandomUnavailable,
+    /// 路径构建中的签名验证次数超过上限
+    TooManySignatureChecks,
+}
+
+impl fmt::Display for X509Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            X509Error::InvalidEncoding => write!(f, "Malformed certificate encoding"),
+            X509Error::UnsupportedAlgorithm => write!(f, "Unsupported signature or public key algorithm"),
+            X509Error::InvalidSignature => write!(f, "Signature verification failed"),
+            X509Error::NotYetValid => write!(f, "Certificate is not yet valid"),
+            X509Error::Expired => write!(f, "Certificate has expired"),
+            X509Error::UnknownIssuer => write!(f, "No trusted issuer found"),
+            X509Error::NotCa => write!(f, "Issuer is not allowed to sign certificates"),
+            X509Error::PathLengthExceeded => write!(f, "Path length constraint exceeded"),
+            X509Error::UnsupportedCriticalExtension => write!(f, "Unsupported critical extension"),
+            X509Error::KeyMismatch => write!(f, "Signing key does not match the certificate"),
+            X509Error::InvalidSerialNumber => write!(f, "Serial number must be positive and at most 20 octets"),
+            X509Error::InvalidValidity => write!(f, "Validity period ends before it begins"),
+            X509Error::RandomUnavailable => write!(f, "Secure random number generator unavailable"),
+            X509Error::TooManySignatureChecks => write!(f, "Path building exceeded the signature check limit"),
+        }
+    }
+}
+
+impl Error for X509Error {}
+
+impl From<DerError> for X509Error {
+    fn from(_: DerError) -> Self {
+        X509Error::InvalidEncoding
+    }
+}
+
+impl From<SM2Error> for X509Error {
+    fn from(e: SM2Error) -> Self {
+        match e {
+            SM2Error::UnsupportedAlgorithm => X509Error::UnsupportedAlgorithm,
+            SM2Error::RandomUnavailable => X509Error::RandomUnavailable,
+            _ => X509Error::InvalidEncoding,
+        }
+    }
+}
+
+/// 解析 SM2-with-SM3 的 AlgorithmIdentifier，参数可省略或为 NULL
+pub(crate) fn parse_signature_algorithm(reader: &mut Reader) -> Result<(), X509Error> {
+    let mut alg = reader.sequence()?;
+    if alg.oid()? != oid::SM2_WITH_SM3 {
+        return Err(X509Error::UnsupportedAlgorithm);
+    }
+    alg.optional_null()?;
+    alg.finish()?;
+    Ok(())
+}
+
+/// SM2-with-SM3 的 AlgorithmIdentifier，按 GM/T 0015 省略参数
+pub(crate) fn signature_algorithm() -> Vec<u8> {
+    der::sequence(&[&der::oid(oid::SM2_WITH_SM3)])
+}
+
+/// 已解析的证书
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    der: Vec<u8>,
+    tbs: Range<usize>,
+    version: u8,
+    serial: Vec<u8>,
+    issuer: Name,
+    subject: Name,
+    not_before: SystemTime,
+    not_after: SystemTime,
+    public_key: VerifyingKey,
+    extensions: Vec<Extension>,
+    parsed: ext::Parsed,
+    signature: Signature,
+}
+
+impl Certificate {
+    pub fn from_der(bytes: &[u8]) -> Result<Self, X509Error> {
+        let mut outer = Reader::new(bytes);
+        let content = outer.read(der::SEQUENCE)?;
+        outer.finish()?;
+        let header = bytes.len() - content.len();
+        let mut seq = Reader::new(content);
+        let tbs_raw = seq.read_raw(der::SEQUENCE)?;
+        parse_signature_algorithm(&mut seq)?;
+        let signature = Signature::from_der(seq.bit_string()?).map_err(|_| X509Error::InvalidSignature)?;
+        seq.finish()?;
+
+        let mut tbs = Reader::new(tbs_raw).sequence()?;
+        // version [0] EXPLICIT，缺省为 v1
+        let version = match tbs.read_optional(der::context(0))? {
+            Some(v) => {
+                let mut v = Reader::new(v);
+                let version = v.small_uint()?;
+                v.finish()?;
+                if version > 2 {
+                    return Err(X509Error::InvalidEncoding);
+                }
+                version as u8 + 1
+            }
+            None => 1,
+        };
+        let serial = tbs.uint()?.to_vec();
+        // TBS 中的签名算法必须与外层一致
+        parse_signature_algorithm(&mut tbs)?;
+        let issuer = Name::parse(tbs.read_raw(der::SEQUENCE)?)?;
+        let mut validity = tbs.sequence()?;
+        let not_before = validity.read_any().and_then(|(tag, t)| time::parse(tag, t))?;
+        let not_after = validity.read_any().and_then(|(tag, t)| time::parse(tag, t))?;
+        validity.finish()?;
+        let subject = Name::parse(tbs.read_raw(der::SEQUENCE)?)?;
+        let public_key = VerifyingKey::parse_spki(&mut tbs.sequence()?)?;
+        // issuerUniqueID [1]、subjectUniqueID [2] 被忽略
+        tbs.read_optional(0x81)?;
+        tbs.read_optional(0x82)?;
+        let (extensions, parsed) = match tbs.read_optional(der::context(3))? {
+            Some(_) if version != 3 => return Err(X509Error::InvalidEncoding),
+            Some(exts) => ext::parse_extensions(exts)?,
+            None => (Vec::new(), ext::Parsed::default()),
+        };
+        tbs.finish()?;
+
+        Ok(Certificate {
+            der: bytes.to_vec(),
+            tbs: header..header + tbs_raw.len(),
+            version,
+            serial,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            public_key,
+            extensions,
+            parsed,
+            signature,
+        })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, X509Error> {
+        let bytes = pem::decode(text, CERTIFICATE_LABEL).ok_or(X509Error::InvalidEncoding)?;
+        Self::from_der(&bytes)
+    }
+
+    /// 按顺序解析文本中的所有证书
+    pub fn from_pem_chain(text: &str) -> Result<Vec<Self>, X509Error> {
+        let blocks = pem::decode_all(text, CERTIFICATE_LABEL).ok_or(X509Error::InvalidEncoding)?;
+        blocks.iter().map(|der| Self::from_der(der)).collect()
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode(CERTIFICATE_LABEL, &self.der)
+    }
+
+    /// 被签名的 `TBSCertificate` 编码
+    pub fn tbs_certificate(&self) -> &[u8] {
+        &self.der[self.tbs.clone()]
+    }
+
+    /// 1、2 或 3
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// 序列号，大端字节，不含符号位填充
+    pub fn serial_number(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    pub fn subject(&self) -> &Name {
+        &self.subject
+    }
+
+    pub fn not_before(&self) -> SystemTime {
+        self.not_before
+    }
+
+    pub fn not_after(&self) -> SystemTime {
+        self.not_after
+    }
+
+    /// 证书公钥，使用默认ID
+    pub fn public_key(&self) -> &VerifyingKey {
+        &self.public_key
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// 全部扩展，包括已解析的
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn key_usage(&self) -> Option<KeyUsage> {
+        self.parsed.key_usage
+    }
+
+    pub fn basic_constraints(&self) -> Option<BasicConstraints> {
+        self.parsed.basic_constraints
+    }
+
+    /// 扩展密钥用途的 OID 列表
+    pub fn extended_key_usage(&self) -> Option<&[String]> {
+        self.parsed.ext_key_usage.as_deref()
+    }
+
+    pub fn subject_alt_names(&self) -> &[GeneralName] {
+        &self.parsed.subject_alt_names
+    }
+
+    pub fn subject_key_id(&self) -> Option<&[u8]> {
+        self.parsed.subject_key_id.as_deref()
+    }
+
+    pub fn authority_key_id(&self) -> Option<&[u8]> {
+        self.parsed.authority_key_id.as_deref()
+    }
+
+    /// 基本约束中 cA 为真
+    pub fn is_ca(&self) -> bool {
+        self.parsed.basic_constraints.is_some_and(|bc| bc.ca)
+    }
+
+    /// 颁发者与主体名称相同
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer == self.subject
+    }
+
+    /// 检查 `time` 是否在有效期内（含两端）
+    pub fn check_validity(&self, time: SystemTime) -> Result<(), X509Error> {
+        if time < self.not_before {
+            Err(X509Error::NotYetValid)
+        } else if time > self.not_after {
+            Err(X509Error::Expired)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 用颁发者公钥验证证书签名
+    pub fn verify_signature(&self, issuer_key: &VerifyingKey) -> Result<(), X509Error> {
+        if issuer_key.verify(self.tbs_certificate(), &self.signature) { Ok(()) } else { Err(X509Error::InvalidSignature) }
+    }
+
+    /// 检查名称匹配并验证 `issuer` 对本证书的签名
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<(), X509Error> {
+        if self.issuer != issuer.subject {
+            return Err(X509Error::UnknownIssuer);
+        }
+        self.verify_signature(issuer.public_key())
+    }
+
+    /// 整个证书 DER 编码的 SM3 摘要
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut sm3 = Sm3::new();
+        sm3.update(&self.der);
+        sm3.finish();
+        *sm3.hash_bytes()
+    }
+
+    /// 存在本实现不处理的关键扩展
+    pub(crate) fn has_unhandled_critical_extension(&self) -> bool {
+        self.parsed.unhandled_critical
+    }
+}
+
+/// 按 DER 编码比较
+impl PartialEq for Certificate {
+    fn eq(&self, other: &Self) -> bool {
+        self.der == other.der
+    }
+}
+
+impl Eq for Certificate {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 由 OpenSSL 3 生成的 SM2 证书链：根 CA -> 中间 CA (pathlen:0) -> 终端证书
+    // OpenSSL 默认使用空ID签名，生成时需指定 `-sigopt distid:1234567812345678`
+    pub(crate) const ROOT: &str = "-----BEGIN CERTIFICATE-----
+MIIBojCCAUmgAwIBAgIBATAKBggqgRzPVQGDdTA5MQswCQYDVQQGEwJDTjEQMA4G
+A1UECgwHR00gVGVzdDEYMBYGA1UEAwwPR00gVGVzdCBSb290IENBMB4XDTI0MDEw
+MTAwMDAwMFoXDTQ0MDEwMTAwMDAwMFowOTELMAkGA1UEBhMCQ04xEDAOBgNVBAoM
+B0dNIFRlc3QxGDAWBgNVBAMMD0dNIFRlc3QgUm9vdCBDQTBZMBMGByqGSM49AgEG
+CCqBHM9VAYItA0IABLMQx3khBaBeKeU87RipiSTvfzHoFPV2Z9fhSsnziJRBd7J8
+LudrMn+pkGmq3kgKNma1NaUKP6944vzxt2kcyjejQjBAMA8GA1UdEwEB/wQFMAMB
+Af8wDgYDVR0PAQH/BAQDAgEGMB0GA1UdDgQWBBS72re3RdTvBid1ws0S6tKHBu+1
+JTAKBggqgRzPVQGDdQNHADBEAiBr88AILvkwavTkuUs2DrI7imLWPvI7qRZKvW8A
+OgqHLQIgYqkS64D+2MOEsh5fsZSr6e3RjXoW6m4EeaDX26yobkE=
+-----END CERTIFICATE-----
+";
+    pub(crate) const INTERMEDIATE: &str = "-----BEGIN CERTIFICATE-----
+MIIBzjCCAXWgAwIBAgIBAjAKBggqgRzPVQGDdTA5MQswCQYDVQQGEwJDTjEQMA4G
+A1UECgwHR00gVGVzdDEYMBYGA1UEAwwPR00gVGVzdCBSb290IENBMB4XDTI0MDEw
+MTAwMDAwMFoXDTM0MDEwMTAwMDAwMFowQTELMAkGA1UEBhMCQ04xEDAOBgNVBAoM
+B0dNIFRlc3QxIDAeBgNVBAMMF0dNIFRlc3QgSW50ZXJtZWRpYXRlIENBMFkwEwYH
+KoZIzj0CAQYIKoEcz1UBgi0DQgAEVDUbwA+wHxKSwNuSS6xSZdB3+3FAn5nB2veK
+5MyMCV6jox8KNIOA/JCwxBTR7s392isaBoqi4JsPxMOKKNSz66NmMGQwEgYDVR0T
+AQH/BAgwBgEB/wIBADAOBgNVHQ8BAf8EBAMCAQYwHQYDVR0OBBYEFIHDj9IVc67I
+D5ZUwzK4Fi43hLO6MB8GA1UdIwQYMBaAFLvat7dF1O8GJ3XCzRLq0ocG77UlMAoG
+CCqBHM9VAYN1A0cAMEQCIA+spzrRZLLJbg1wosj0S5mxIJh8iu32AYtstudtV/Tf
+AiAIethWBL1JrtBH6sw8/hr4Rd6nv/2KA6Jz8/qlldUx3Q==
+-----END CERTIFICATE-----
+";
+    pub(crate) const LEAF: &str = "-----BEGIN CERTIFICATE-----
+MIICNzCCAd6gAwIBAgIIASNFZ4mrze8wCgYIKoEcz1UBg3UwQTELMAkGA1UEBhMC
+Q04xEDAOBgNVBAoMB0dNIFRlc3QxIDAeBgNVBAMMF0dNIFRlc3QgSW50ZXJtZWRp
+YXRlIENBMB4XDTI1MDEwMTAwMDAwMFoXDTI2MDEwMTAwMDAwMFowRjELMAkGA1UE
+BhMCQ04xEDAOBgNVBAgMB0JlaWppbmcxEDAOBgNVBAoMB0dNIFRlc3QxEzARBgNV
+BAMMCmV4YW1wbGUuY24wWTATBgcqhkjOPQIBBggqgRzPVQGCLQNCAARz7hpEvQ1C
+vPYNdaH832ymSIsr5SbTJ2u8d+eWmRhVH77W+0p70F1UgC1E8llwF8MTPS2VeR3D
+16TIqXDQtjvpo4G6MIG3MAkGA1UdEwQCMAAwDgYDVR0PAQH/BAQDAgWgMB0GA1Ud
+JQQWMBQGCCsGAQUFBwMBBggrBgEFBQcDAjA7BgNVHREENDAyggpleGFtcGxlLmNu
+ggwqLmV4YW1wbGUuY26HBMCoAQGBEGFkbWluQGV4YW1wbGUuY24wHQYDVR0OBBYE
+FHcf3HjkREdDA1rOL334ZsFN95WbMB8GA1UdIwQYMBaAFIHDj9IVc67ID5ZUwzK4
+Fi43hLO6MAoGCCqBHM9VAYN1A0cAMEQCICiOrRkTdyF0oLs2rLVs1g6rrcc1RbU9
+ydsOq41U8ycHAiAcliqeQdN87rVhhcgoNLRM+nJRg/HA3VinbvVNO0nUSQ==
+-----END CERTIFICATE-----
+";
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    #[test]
+    fn test_parse_leaf() {
+        let cert = Certificate::from_pem(LEAF).unwrap();
+        assert_eq!(cert.version(), 3);
+        assert_eq!(cert.serial_number(), [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+        assert_eq!(cert.issuer().to_string(), "C=CN, O=GM Test, CN=GM Test Intermediate CA");
+        assert_eq!(cert.subject().to_string(), "C=CN, ST=Beijing, O=GM Test, CN=example.cn");
+        assert_eq!(cert.subject().common_name(), Some("example.cn"));
+        assert_eq!(cert.not_before(), at(1735689600));
+        assert_eq!(cert.not_after(), at(1767225600));
+        assert_eq!(&hex(&cert.public_key().to_sec1_bytes())[..10], "0473EE1A44");
+
+        assert_eq!(cert.basic_constraints(), Some(BasicConstraints { ca: false, path_len: None }));
+        assert!(!cert.is_ca());
+        assert_eq!(cert.key_usage(), Some(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_ENCIPHERMENT));
+        assert_eq!(cert.extended_key_usage().unwrap(), [eku::SERVER_AUTH, eku::CLIENT_AUTH]);
+        assert_eq!(
+            cert.subject_alt_names(),
+            [
+                GeneralName::Dns("example.cn".into()),
+                GeneralName::Dns("*.example.cn".into()),
+                GeneralName::Ip("192.168.1.1".parse().unwrap()),
+                GeneralName::Email("admin@example.cn".into()),
+            ]
+        );
+        assert_eq!(hex(cert.subject_key_id().unwrap()), "771FDC78E4444743035ACE2F7DF866C14DF7959B");
+        assert_eq!(hex(cert.authority_key_id().unwrap()), "81C38FD21573AEC80F9654C332B8162E3784B3BA");
+        assert_eq!(cert.extensions().len(), 6);
+        assert!(cert.extensions().iter().any(|e| e.oid == "2.5.29.15" && e.critical));
+
+        // 重新编码为 PEM 与原文一致
+        assert_eq!(cert.to_pem(), LEAF);
+        assert_eq!(Certificate::from_der(cert.as_der()).unwrap(), cert);
+    }
+
+    #[test]
+    fn test_parse_ca() {
+        let root = Certificate::from_pem(ROOT).unwrap();
+        assert!(root.is_ca() && root.is_self_issued());
+        assert_eq!(root.key_usage(), Some(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN));
+        let inter = Certificate::from_pem(INTERMEDIATE).unwrap();
+        assert_eq!(inter.basic_constraints(), Some(BasicConstraints { ca: true, path_len: Some(0) }));
+        assert_eq!(inter.serial_number(), [2]);
+        assert_eq!(inter.authority_key_id(), root.subject_key_id());
+        // openssl x509 -fingerprint -sm3
+        assert_eq!(hex(&inter.fingerprint()), "8C5A0A8ECE18395BD32D44BE3BC8A3DED00D23125B233B77B52861F4D292FF5B");
+    }
+
+    #[test]
+    fn test_signatures() {
+        let root = Certificate::from_pem(ROOT).unwrap();
+        let inter = Certificate::from_pem(INTERMEDIATE).unwrap();
+        let leaf = Certificate::from_pem(LEAF).unwrap();
+        root.verify_issued_by(&root).unwrap();
+        inter.verify_issued_by(&root).unwrap();
+        leaf.verify_issued_by(&inter).unwrap();
+        assert_eq!(leaf.verify_issued_by(&root), Err(X509Error::UnknownIssuer));
+        assert_eq!(leaf.verify_signature(root.public_key()), Err(X509Error::InvalidSignature));
+
+        let chain = Certificate::from_pem_chain(&[LEAF, INTERMEDIATE, ROOT].concat()).unwrap();
+        assert_eq!(chain, [leaf.clone(), inter, root]);
+
+        // 篡改 TBS 中的主体名称
+        let mut der = leaf.as_der().to_vec();
+        let pos = der.windows(10).position(|w| w == b"example.cn").unwrap();
+        der[pos] = b'E';
+        let tampered = Certificate::from_der(&der).unwrap();
+        assert_eq!(tampered.subject().common_name(), Some("Example.cn"));
+        let inter = Certificate::from_pem(INTERMEDIATE).unwrap();
+        assert_eq!(tampered.verify_issued_by(&inter), Err(X509Error::InvalidSignature));
+    }
+
+    #[test]
+    fn test_validity() {
+        let leaf = Certificate::from_pem(LEAF).unwrap();
+        assert_eq!(leaf.check_validity(at(1735689599)), Err(X509Error::NotYetValid));
+        assert_eq!(leaf.check_validity(at(1735689600)), Ok(()));
+        assert_eq!(leaf.check_validity(at(1767225600)), Ok(()));
+        assert_eq!(leaf.check_validity(at(1767225601)), Err(X509Error::Expired));
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        let der = Certificate::from_pem(LEAF).unwrap().as_der().to_vec();
+        assert_eq!(Certificate::from_der(&der[..der.len() - 1]).err(), Some(X509Error::InvalidEncoding));
+        assert!(Certificate::from_der(&[&der[..], &[0]].concat()).is_err());
+        assert!(Certificate::from_pem("").is_err());
+
+        // 把签名算法改为 ecdsa-with-SHA256 (1.2.840.10045.4.3.2)
+        let sm2_sm3 = der::oid(oid::SM2_WITH_SM3);
+        let ecdsa = der::oid(&[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]);
+        assert_eq!(sm2_sm3.len(), ecdsa.len());
+        let pos = der.windows(sm2_sm3.len()).rposition(|w| w == sm2_sm3).unwrap();
+        let mut other = der.clone();
+        other[pos..pos + ecdsa.len()].copy_from_slice(&ecdsa);
+        assert_eq!(Certificate::from_der(&other).err(), Some(X509Error::UnsupportedAlgorithm));
+    }
+}
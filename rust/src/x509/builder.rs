@@ -0,0 +1,317 @@
+//! 用 SM2-with-SM3 签发证书
+//!
+//! 签名总是使用默认ID，与 GM/T 0015 及 [`Certificate::verify_signature`] 一致。
+
+use std::time::SystemTime;
+
+use crate::der;
+use crate::sm2::{DEFAULT_DISTID, SigningKey, VerifyingKey};
+use crate::sm3::Sm3;
+use crate::util::getrandom;
+
+use super::ext::{self, id};
+use super::{BasicConstraints, Certificate, CertificateRequest, GeneralName, KeyUsage, Name, X509Error, time};
+
+/// 证书与证书请求共用的扩展设置
+#[derive(Clone, Debug, Default)]
+pub(super) struct ExtensionSet {
+    pub(super) key_usage: Option<KeyUsage>,
+    pub(super) ext_key_usage: Vec<String>,
+    pub(super) basic_constraints: Option<BasicConstraints>,
+    pub(super) subject_alt_names: Vec<GeneralName>,
+}
+
+impl ExtensionSet {
+    /// 按 RFC 5280 设置关键标志：基本约束与密钥用途为关键扩展，主体为空时主体备用名为关键扩展
+    pub(super) fn encode(&self, subject: &Name) -> Result<Vec<Vec<u8>>, X509Error> {
+        let mut out = Vec::new();
+        if let Some(bc) = self.basic_constraints {
+            out.push(ext::encode_extension(id::BASIC_CONSTRAINTS, true, &bc.to_der()));
+        }
+        if let Some(ku) = self.key_usage {
+            out.push(ext::encode_extension(id::KEY_USAGE, true, &ku.to_der()));
+        }
+        if !self.ext_key_usage.is_empty() {
+            let value = ext::encode_ext_key_usage(&self.ext_key_usage).ok_or(X509Error::InvalidEncoding)?;
+            out.push(ext::encode_extension(id::EXT_KEY_USAGE, false, &value));
+        }
+        if !self.subject_alt_names.is_empty() {
+            let critical = subject.attributes().next().is_none();
+            out.push(ext::encode_extension(id::SUBJECT_ALT_NAME, critical, &ext::encode_general_names(&self.subject_alt_names)));
+        }
+        Ok(out)
+    }
+}
+
+/// 用默认ID签名，返回 `SEQUENCE { tbs, 签名算法, 签名 }`
+pub(super) fn sign_der(tbs: &[u8], key: &SigningKey) -> Result<Vec<u8>, X509Error> {
+    let signature = if key.distid() == DEFAULT_DISTID {
+        key.try_sign(tbs)?
+    } else {
+        key.clone().with_distid(DEFAULT_DISTID).unwrap().try_sign(tbs)?
+    };
+    Ok(der::sequence(&[tbs, &super::signature_algorithm(), &der::bit_string(&signature.to_der())]))
+}
+
+/// 密钥标识：公钥（未压缩点）SM3 摘要的前160位（RFC 7093 方法1）
+pub(crate) fn key_identifier(key: &VerifyingKey) -> [u8; 20] {
+    let mut sm3 = Sm3::new();
+    sm3.update(&key.to_sec1_bytes());
+    sm3.finish();
+    sm3.hash_bytes()[..20].try_into().unwrap()
+}
+
+fn same_key(a: &VerifyingKey, b: &VerifyingKey) -> bool {
+    a.to_sec1_bytes() == b.to_sec1_bytes()
+}
+
+/// RFC 5280 4.1.2.2：序列号为正整数，DER 编码的内容不超过20字节
+fn check_serial(serial: &[u8]) -> Result<(), X509Error> {
+    let skip = serial.iter().take_while(|&&b| b == 0).count();
+    let serial = &serial[skip..];
+    let encoded_len = serial.len() + usize::from(serial.first().is_some_and(|b| b & 0x80 != 0));
+    if serial.is_empty() || encoded_len > 20 {
+        return Err(X509Error::InvalidSerialNumber);
+    }
+    Ok(())
+}
+
+/// 随机的128位正序列号
+fn random_serial() -> Result<Vec<u8>, X509Error> {
+    let mut serial = [0u8; 16];
+    getrandom(&mut serial).map_err(|_| X509Error::RandomUnavailable)?;
+    // 保证为正且长度固定
+    serial[0] = (serial[0] & 0x7f) | 0x40;
+    Ok(serial.to_vec())
+}
+
+/// X.509 v3 证书构造器
+///
+/// 总会添加主体密钥标识；CA 签发时还会添加颁发者密钥标识。
+#[derive(Clone, Debug)]
+pub struct CertificateBuilder {
+    subject: Name,
+    public_key: VerifyingKey,
+    serial: Option<Vec<u8>>,
+    not_before: SystemTime,
+    not_after: SystemTime,
+    extensions: ExtensionSet,
+}
+
+impl CertificateBuilder {
+    pub fn new(subject: Name, public_key: &VerifyingKey, not_before: SystemTime, not_after: SystemTime) -> Self {
+        CertificateBuilder {
+            subject,
+            public_key: public_key.clone(),
+            serial: None,
+            not_before,
+            not_after,
+            extensions: ExtensionSet::default(),
+        }
+    }
+
+    /// 沿用证书请求中的主体、公钥、密钥用途、扩展密钥用途与主体备用名
+    ///
+    /// 请求中的基本约束被忽略，由签发方决定；请求签名无效时返回错误。
+    pub fn from_request(csr: &CertificateRequest, not_before: SystemTime, not_after: SystemTime) -> Result<Self, X509Error> {
+        csr.verify()?;
+        let mut builder = Self::new(csr.subject().clone(), csr.public_key(), not_before, not_after);
+        builder.extensions.key_usage = csr.key_usage();
+        builder.extensions.ext_key_usage = csr.extended_key_usage().map_or(Vec::new(), |e| e.to_vec());
+        builder.extensions.subject_alt_names = csr.subject_alt_names().to_vec();
+        Ok(builder)
+    }
+
+    /// 序列号（大端），未设置时随机生成128位正整数
+    ///
+    /// 必须为正且 DER 编码不超过20字节（最高位为1时编码会多一个前导零），否则签发时返回
+    /// [`X509Error::InvalidSerialNumber`]
+    pub fn serial_number(mut self, serial: &[u8]) -> Self {
+        self.serial = Some(serial.to_vec());
+        self
+    }
+
+    pub fn key_usage(mut self, usage: KeyUsage) -> Self {
+        self.extensions.key_usage = Some(usage);
+        self
+    }
+
+    /// 扩展密钥用途，见 [`eku`](super::eku)
+    pub fn extended_key_usage(mut self, oids: &[&str]) -> Self {
+        self.extensions.ext_key_usage = oids.iter().map(|o| o.to_string()).collect();
+        self
+    }
+
+    pub fn basic_constraints(mut self, constraints: BasicConstraints) -> Self {
+        self.extensions.basic_constraints = Some(constraints);
+        self
+    }
+
+    /// 追加一个主体备用名
+    pub fn subject_alt_name(mut self, name: GeneralName) -> Self {
+        self.extensions.subject_alt_names.push(name);
+        self
+    }
+
+    /// 自签名，`key` 必须与证书公钥对应
+    pub fn self_signed(self, key: &SigningKey) -> Result<Certificate, X509Error> {
+        if !same_key(key.verifying_key(), &self.public_key) {
+            return Err(X509Error::KeyMismatch);
+        }
+        let subject = self.subject.clone();
+        self.build(&subject, None, key)
+    }
+
+    /// 由 `issuer` 签发，`issuer_key` 必须与颁发者证书公钥对应
+    pub fn sign(self, issuer: &Certificate, issuer_key: &SigningKey) -> Result<Certificate, X509Error> {
+        if !same_key(issuer_key.verifying_key(), issuer.public_key()) {
+            return Err(X509Error::KeyMismatch);
+        }
+        let aki = issuer.subject_key_id().map_or_else(|| key_identifier(issuer.public_key()).to_vec(), |id| id.to_vec());
+        self.build(issuer.subject(), Some(&aki), issuer_key)
+    }
+
+    fn build(self, issuer: &Name, authority_key_id: Option<&[u8]>, key: &SigningKey) -> Result<Certificate, X509Error> {
+        // RFC 5280 4.1.2.5
+        if self.not_after < self.not_before {
+            return Err(X509Error::InvalidValidity);
+        }
+        let serial = match &self.serial {
+            Some(serial) => {
+                check_serial(serial)?;
+                serial.clone()
+            }
+            None => random_serial()?,
+        };
+
+        let mut extensions = self.extensions.encode(&self.subject)?;
+        let ski = key_identifier(&self.public_key);
+        extensions.push(ext::encode_extension(id::SUBJECT_KEY_ID, false, &der::octet_string(&ski)));
+        if let Some(aki) = authority_key_id {
+            extensions.push(ext::encode_extension(id::AUTHORITY_KEY_ID, false, &der::sequence(&[&der::tlv(0x80, aki)])));
+        }
+        let extensions = der::sequence(&extensions.iter().map(Vec::as_slice).collect::<Vec<_>>());
+
+        let tbs = der::sequence(&[
+            &der::constructed(der::context(0), &[&der::small_uint(2)]),
+            &der::uint(&serial),
+            &super::signature_algorithm(),
+            issuer.as_der(),
+            &der::sequence(&[&time::encode(self.not_before), &time::encode(self.not_after)]),
+            self.subject.as_der(),
+            &self.public_key.to_public_key_der(),
+            &der::constructed(der::context(3), &[&extensions]),
+        ]);
+        Certificate::from_der(&sign_der(&tbs, key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x509::{TrustStore, attr, eku};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn name(cn: &str) -> Name {
+        Name::new(&[(attr::COUNTRY, "CN"), (attr::ORGANIZATION, "GM Test"), (attr::COMMON_NAME, cn)]).unwrap()
+    }
+
+    fn ca(cn: &str, key: &SigningKey, path_len: Option<u32>) -> CertificateBuilder {
+        CertificateBuilder::new(name(cn), key.verifying_key(), at(1704067200), at(2019686400))
+            .basic_constraints(BasicConstraints { ca: true, path_len })
+            .key_usage(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN)
+    }
+
+    #[test]
+    fn test_issue_chain() {
+        let (root_key, inter_key, leaf_key) = (SigningKey::random(), SigningKey::random(), SigningKey::random());
+        let root = ca("Root", &root_key, None).serial_number(&[1]).self_signed(&root_key).unwrap();
+        let inter = ca("Intermediate", &inter_key, Some(0)).sign(&root, &root_key).unwrap();
+        let leaf = CertificateBuilder::new(name("example.cn"), leaf_key.verifying_key(), at(1735689600), at(1767225600))
+            .key_usage(KeyUsage::DIGITAL_SIGNATURE)
+            .extended_key_usage(&[eku::SERVER_AUTH])
+            .subject_alt_name(GeneralName::Dns("example.cn".into()))
+            .subject_alt_name(GeneralName::Ip("10.0.0.1".parse().unwrap()))
+            .sign(&inter, &inter_key)
+            .unwrap();
+
+        assert_eq!(root.serial_number(), [1]);
+        assert!(root.is_self_issued() && root.is_ca());
+        assert_eq!(root.authority_key_id(), None);
+        assert_eq!(root.subject_key_id().unwrap(), key_identifier(root_key.verifying_key()));
+        assert_eq!(inter.authority_key_id(), root.subject_key_id());
+        assert_eq!(inter.basic_constraints(), Some(BasicConstraints { ca: true, path_len: Some(0) }));
+        assert_eq!(inter.serial_number().len(), 16);
+
+        assert_eq!(leaf.version(), 3);
+        assert_eq!(leaf.issuer(), inter.subject());
+        assert_eq!(leaf.not_before(), at(1735689600));
+        assert_eq!(leaf.key_usage(), Some(KeyUsage::DIGITAL_SIGNATURE));
+        assert_eq!(leaf.extended_key_usage().unwrap(), [eku::SERVER_AUTH]);
+        assert_eq!(leaf.subject_alt_names().len(), 2);
+        assert_eq!(leaf.basic_constraints(), None);
+
+        let store = TrustStore::from_pem(&root.to_pem()).unwrap();
+        let path = store.verify(&leaf, std::slice::from_ref(&inter), at(1750000000)).unwrap();
+        assert_eq!(path, [leaf, inter, root]);
+    }
+
+    #[test]
+    fn test_path_length() {
+        let (root_key, inter_key, sub_key, leaf_key) = (SigningKey::random(), SigningKey::random(), SigningKey::random(), SigningKey::random());
+        let root = ca("Root", &root_key, None).self_signed(&root_key).unwrap();
+        let inter = ca("Intermediate", &inter_key, Some(0)).sign(&root, &root_key).unwrap();
+        let sub = ca("Sub", &sub_key, None).sign(&inter, &inter_key).unwrap();
+        let leaf = CertificateBuilder::new(name("leaf"), leaf_key.verifying_key(), at(1735689600), at(1767225600)).sign(&sub, &sub_key).unwrap();
+
+        let mut store = TrustStore::new();
+        store.add(root);
+        let now = at(1750000000);
+        assert_eq!(store.verify(&sub, std::slice::from_ref(&inter), now).unwrap().len(), 3);
+        assert_eq!(store.verify(&leaf, &[inter, sub], now), Err(X509Error::PathLengthExceeded));
+    }
+
+    #[test]
+    fn test_key_checks() {
+        let key = SigningKey::random();
+        let other = SigningKey::random();
+        let builder = ca("Root", &key, None);
+        assert_eq!(builder.clone().self_signed(&other).err(), Some(X509Error::KeyMismatch));
+        let root = builder.self_signed(&key).unwrap();
+        let child = CertificateBuilder::new(name("leaf"), other.verifying_key(), at(1735689600), at(1767225600));
+        assert_eq!(child.sign(&root, &other).err(), Some(X509Error::KeyMismatch));
+
+        // 签名方自定义ID不影响证书签名
+        let custom = key.clone().with_distid(b"alice").unwrap();
+        let cert = ca("Root", &custom, None).self_signed(&custom).unwrap();
+        cert.verify_issued_by(&cert).unwrap();
+
+        // 空主体时主体备用名为关键扩展
+        let cert = CertificateBuilder::new(Name::new(&[]).unwrap(), other.verifying_key(), at(0), at(1))
+            .subject_alt_name(GeneralName::Email("a@b.cn".into()))
+            .sign(&root, &key)
+            .unwrap();
+        assert!(cert.extensions().iter().any(|e| e.oid == "2.5.29.17" && e.critical));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let key = SigningKey::random();
+        let builder = ca("Root", &key, None);
+        for serial in [&[][..], &[0], &[0, 0], &[0x80; 20], &[1; 21]] {
+            assert_eq!(builder.clone().serial_number(serial).self_signed(&key).err(), Some(X509Error::InvalidSerialNumber), "{:02x?}", serial);
+        }
+        // 前导零不计入长度，最高位为0时20字节可用
+        let cert = builder.clone().serial_number(&[[0].as_slice(), &[0x7f; 20]].concat()).self_signed(&key).unwrap();
+        assert_eq!(cert.serial_number(), [0x7f; 20]);
+
+        let reversed = CertificateBuilder::new(name("Root"), key.verifying_key(), at(1767225600), at(1735689600));
+        assert_eq!(reversed.self_signed(&key).err(), Some(X509Error::InvalidValidity));
+        let instant = CertificateBuilder::new(name("Root"), key.verifying_key(), at(1735689600), at(1735689600));
+        assert!(instant.self_signed(&key).is_ok());
+    }
+}
@@ -0,0 +1,287 @@
+//! 证书路径构建与验证（RFC 5280 第6章的简化实现）
+//!
+//! 检查签名、有效期、CA 基本约束、keyCertSign 密钥用途、路径长度约束与未知关键扩展；
+//! 不处理证书策略与名称约束。
+
+use std::time::SystemTime;
+
+use super::{Certificate, KeyUsage, X509Error};
+
+/// 路径中中间证书数量上限
+const MAX_INTERMEDIATES: usize = 8;
+
+/// 单次验证中签发者检查（含签名验证）的总次数上限，防止同名证书使搜索指数增长
+const MAX_SIGNATURE_CHECKS: usize = 100;
+
+/// 信任锚（根证书）集合
+#[derive(Clone, Debug, Default)]
+pub struct TrustStore {
+    anchors: Vec<Certificate>,
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从 PEM 文本读入全部证书作为信任锚
+    pub fn from_pem(text: &str) -> Result<Self, X509Error> {
+        Ok(TrustStore { anchors: Certificate::from_pem_chain(text)? })
+    }
+
+    /// 添加信任锚，重复添加被忽略
+    pub fn add(&mut self, cert: Certificate) {
+        if !self.anchors.contains(&cert) {
+            self.anchors.push(cert);
+        }
+    }
+
+    pub fn anchors(&self) -> &[Certificate] {
+        &self.anchors
+    }
+
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    /// 在 `time` 时刻验证 `leaf`，必要时从 `intermediates` 中选取中间证书
+    ///
+    /// 成功时返回证书路径，`leaf` 在前，信任锚在后。
+    pub fn verify(&self, leaf: &Certificate, intermediates: &[Certificate], time: SystemTime) -> Result<Vec<Certificate>, X509Error> {
+        check_certificate(leaf, time)?;
+        let mut path = vec![leaf];
+        let mut budget = MAX_SIGNATURE_CHECKS;
+        self.build(&mut path, intermediates, time, &mut budget)?;
+        Ok(path.into_iter().cloned().collect())
+    }
+
+    /// 深度优先搜索，从 `path` 的末尾继续向上构建，`budget` 耗尽时立即终止
+    fn build<'a>(
+        &'a self,
+        path: &mut Vec<&'a Certificate>,
+        intermediates: &'a [Certificate],
+        time: SystemTime,
+        budget: &mut usize,
+    ) -> Result<(), X509Error> {
+        let cert = *path.last().unwrap();
+        if self.anchors.contains(cert) {
+            return Ok(());
+        }
+
+        let mut error = X509Error::UnknownIssuer;
+        for anchor in self.anchors.iter().filter(|a| may_issue(a, cert)) {
+            spend(budget)?;
+            match check_issuer(anchor, true, cert, path.len() - 1, time) {
+                Ok(()) => {
+                    path.push(anchor);
+                    return Ok(());
+                }
+                Err(e) => error = e,
+            }
+        }
+
+        if path.len() > MAX_INTERMEDIATES {
+            return Err(error);
+        }
+        for ca in intermediates.iter().filter(|c| may_issue(c, cert)) {
+            if path.contains(&ca) {
+                continue;
+            }
+            spend(budget)?;
+            if let Err(e) = check_certificate(ca, time).and_then(|_| check_issuer(ca, false, cert, path.len() - 1, time)) {
+                error = e;
+                continue;
+            }
+            path.push(ca);
+            match self.build(path, intermediates, time, budget) {
+                Ok(()) => return Ok(()),
+                Err(X509Error::TooManySignatureChecks) => return Err(X509Error::TooManySignatureChecks),
+                Err(e) => {
+                    error = e;
+                    path.pop();
+                }
+            }
+        }
+        Err(error)
+    }
+}
+
+fn spend(budget: &mut usize) -> Result<(), X509Error> {
+    *budget = budget.checked_sub(1).ok_or(X509Error::TooManySignatureChecks)?;
+    Ok(())
+}
+
+/// 证书自身的检查：有效期与关键扩展
+fn check_certificate(cert: &Certificate, time: SystemTime) -> Result<(), X509Error> {
+    cert.check_validity(time)?;
+    if cert.has_unhandled_critical_extension() {
+        return Err(X509Error::UnsupportedCriticalExtension);
+    }
+    Ok(())
+}
+
+/// 名称相同，且两者都有密钥标识时必须一致
+fn may_issue(issuer: &Certificate, cert: &Certificate) -> bool {
+    if cert.issuer() != issuer.subject() {
+        return false;
+    }
+    match (cert.authority_key_id(), issuer.subject_key_id()) {
+        (Some(aki), Some(ski)) => aki == ski,
+        _ => true,
+    }
+}
+
+/// 检查 `issuer` 能否签发 `cert`，`below` 为 `cert` 之下（不含终端证书）的中间证书数量
+fn check_issuer(issuer: &Certificate, anchor: bool, cert: &Certificate, below: usize, time: SystemTime) -> Result<(), X509Error> {
+    issuer.check_validity(time)?;
+    // v1 证书没有扩展，只能作为信任锚；中间证书无论版本都必须声明 CA
+    let bc = issuer.basic_constraints();
+    if (!anchor || issuer.version() == 3) && !bc.is_some_and(|bc| bc.ca) {
+        return Err(X509Error::NotCa);
+    }
+    if issuer.key_usage().is_some_and(|ku| !ku.contains(KeyUsage::KEY_CERT_SIGN)) {
+        return Err(X509Error::NotCa);
+    }
+    if let Some(max) = bc.and_then(|bc| bc.path_len)
+        && below > max as usize
+    {
+        return Err(X509Error::PathLengthExceeded);
+    }
+    cert.verify_signature(issuer.public_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::der;
+    use crate::sm2::SigningKey;
+    use crate::x509::builder::sign_der;
+    use crate::x509::tests::{INTERMEDIATE, LEAF, ROOT};
+    use crate::x509::{BasicConstraints, CertificateBuilder, Name, attr, signature_algorithm, time};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 由不是 CA 的 LEAF 签发
+    const NOT_CA_ISSUED: &str = "-----BEGIN CERTIFICATE-----
+MIIBkTCCATegAwIBAgIBBzAKBggqgRzPVQGDdTBGMQswCQYDVQQGEwJDTjEQMA4G
+A1UECAwHQmVpamluZzEQMA4GA1UECgwHR00gVGVzdDETMBEGA1UEAwwKZXhhbXBs
+ZS5jbjAeFw0yNTAxMDEwMDAwMDBaFw0yNjAxMDEwMDAwMDBaMBoxGDAWBgNVBAMM
+D2V2aWwuZXhhbXBsZS5jbjBZMBMGByqGSM49AgEGCCqBHM9VAYItA0IABI1FQQnM
+UCyuUshaQMWny4ODeBR262iZLLYHln211s6Gbg5P7wgFkWnP0Xr6aYQCCUdbrPoY
+PsgAEXVqzWVEokCjQjBAMB0GA1UdDgQWBBSqxuwfkgTau+L+VuCfold6ol5yQDAf
+BgNVHSMEGDAWgBR3H9x45ERHQwNazi99+GbBTfeVmzAKBggqgRzPVQGDdQNIADBF
+AiEA4UbdNWn9MzBqgHND+nGextTlZmUnjIr0bARLveVm9l4CICG1dLYEgsRKEnSR
+dsrLTChhfHKme79wUOIMdX8/w3H4
+-----END CERTIFICATE-----
+";
+
+    // 2025-06-15
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1750000000)
+    }
+
+    fn load() -> (TrustStore, Certificate, Certificate) {
+        let store = TrustStore::from_pem(ROOT).unwrap();
+        (store, Certificate::from_pem(INTERMEDIATE).unwrap(), Certificate::from_pem(LEAF).unwrap())
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let (store, inter, leaf) = load();
+        let path = store.verify(&leaf, std::slice::from_ref(&inter), now()).unwrap();
+        let names: Vec<_> = path.iter().map(|c| c.subject().common_name().unwrap()).collect();
+        assert_eq!(names, ["example.cn", "GM Test Intermediate CA", "GM Test Root CA"]);
+
+        // 中间证书可直接验证；根证书自身即可信
+        assert_eq!(store.verify(&inter, &[], now()).unwrap().len(), 2);
+        assert_eq!(store.verify(&store.anchors()[0], &[], now()).unwrap().len(), 1);
+
+        // 无关证书与重复证书不影响路径构建
+        let noise = [leaf.clone(), store.anchors()[0].clone(), inter.clone(), inter.clone()];
+        assert_eq!(store.verify(&leaf, &noise, now()).unwrap(), path);
+    }
+
+    #[test]
+    fn test_verify_failures() {
+        let (store, inter, leaf) = load();
+        let inters = std::slice::from_ref(&inter);
+        assert_eq!(store.verify(&leaf, &[], now()), Err(X509Error::UnknownIssuer));
+        assert_eq!(TrustStore::new().verify(&leaf, inters, now()), Err(X509Error::UnknownIssuer));
+
+        // 终端证书过期、尚未生效
+        let later = UNIX_EPOCH + Duration::from_secs(1800000000);
+        assert_eq!(store.verify(&leaf, inters, later), Err(X509Error::Expired));
+        let earlier = UNIX_EPOCH + Duration::from_secs(1720000000);
+        assert_eq!(store.verify(&leaf, inters, earlier), Err(X509Error::NotYetValid));
+
+        // 非 CA 证书签发的证书
+        let evil = Certificate::from_pem(NOT_CA_ISSUED).unwrap();
+        evil.verify_issued_by(&leaf).unwrap();
+        assert_eq!(store.verify(&evil, &[inter.clone(), leaf.clone()], now()), Err(X509Error::NotCa));
+
+        // 中间证书也可作为信任锚
+        let mut partial = TrustStore::new();
+        partial.add(inter.clone());
+        partial.add(inter.clone());
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial.verify(&leaf, &[], now()).unwrap().len(), 2);
+        assert_eq!(partial.verify(&evil, std::slice::from_ref(&leaf), now()), Err(X509Error::NotCa));
+    }
+
+    #[test]
+    fn test_v1_intermediate() {
+        let (start, end) = (UNIX_EPOCH, UNIX_EPOCH + Duration::from_secs(2000000000));
+        let (root_key, ca_key) = (SigningKey::random(), SigningKey::random());
+        let root_name = Name::new(&[(attr::COMMON_NAME, "v1 root")]).unwrap();
+        let root = CertificateBuilder::new(root_name.clone(), root_key.verifying_key(), start, end)
+            .basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .self_signed(&root_key)
+            .unwrap();
+        // 没有版本字段与扩展的 v1 证书
+        let tbs = der::sequence(&[
+            &der::small_uint(2),
+            &signature_algorithm(),
+            root_name.as_der(),
+            &der::sequence(&[&time::encode(start), &time::encode(end)]),
+            Name::new(&[(attr::COMMON_NAME, "v1 CA")]).unwrap().as_der(),
+            &ca_key.verifying_key().to_public_key_der(),
+        ]);
+        let ca = Certificate::from_der(&sign_der(&tbs, &root_key).unwrap()).unwrap();
+        assert_eq!(ca.version(), 1);
+        let leaf = CertificateBuilder::new(Name::new(&[(attr::COMMON_NAME, "leaf")]).unwrap(), SigningKey::random().verifying_key(), start, end)
+            .sign(&ca, &ca_key)
+            .unwrap();
+
+        let mut store = TrustStore::new();
+        store.add(root);
+        assert_eq!(store.verify(&leaf, std::slice::from_ref(&ca), now()), Err(X509Error::NotCa));
+        // 作为信任锚时仍可使用
+        let mut trusted = TrustStore::new();
+        trusted.add(ca);
+        assert_eq!(trusted.verify(&leaf, &[], now()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_signature_check_limit() {
+        // 同名同密钥的中间证书互为签发者，且都无法到达信任锚，不设上限时验证次数随层数指数增长
+        let (start, end) = (UNIX_EPOCH, UNIX_EPOCH + Duration::from_secs(2000000000));
+        let key = SigningKey::random();
+        let name = Name::new(&[(attr::COMMON_NAME, "loop CA")]).unwrap();
+        let ca = || CertificateBuilder::new(name.clone(), key.verifying_key(), start, end).basic_constraints(BasicConstraints { ca: true, path_len: None });
+        let root = ca().self_signed(&key).unwrap();
+        let loops: Vec<_> = (0..10).map(|_| ca().sign(&root, &key).unwrap()).collect();
+        let leaf = CertificateBuilder::new(Name::new(&[(attr::COMMON_NAME, "leaf")]).unwrap(), SigningKey::random().verifying_key(), start, end)
+            .sign(&root, &key)
+            .unwrap();
+
+        let (store, _, _) = load();
+        assert_eq!(store.verify(&leaf, &loops, now()), Err(X509Error::TooManySignatureChecks));
+        // 信任该名称后第一层即可完成
+        let mut trusted = TrustStore::new();
+        trusted.add(root);
+        assert_eq!(trusted.verify(&leaf, &loops, now()).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,393 @@
+//! 证书撤销列表（RFC 5280 第5章）
+//!
+//! 只处理完整 CRL；间接 CRL、增量 CRL 与分发点等关键扩展会使验证失败。
+
+use std::ops::Range;
+use std::time::SystemTime;
+
+use crate::der::{self, Reader};
+use crate::pem;
+use crate::sm2::Signature;
+
+use super::ext;
+use super::{Certificate, Extension, KeyUsage, Name, X509Error, time};
+
+const CRL_LABEL: &str = "X509 CRL";
+
+/// 撤销原因（CRLReason）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RevocationReason {
+    Unspecified,
+    KeyCompromise,
+    CaCompromise,
+    AffiliationChanged,
+    Superseded,
+    CessationOfOperation,
+    CertificateHold,
+    /// 只出现在增量 CRL 中，表示解除撤销
+    RemoveFromCrl,
+    PrivilegeWithdrawn,
+    AaCompromise,
+}
+
+impl RevocationReason {
+    fn from_code(code: u8) -> Option<Self> {
+        use RevocationReason::*;
+        Some(match code {
+            0 => Unspecified,
+            1 => KeyCompromise,
+            2 => CaCompromise,
+            3 => AffiliationChanged,
+            4 => Superseded,
+            5 => CessationOfOperation,
+            6 => CertificateHold,
+            8 => RemoveFromCrl,
+            9 => PrivilegeWithdrawn,
+            10 => AaCompromise,
+            _ => return None,
+        })
+    }
+}
+
+/// CRL 中的一个撤销条目
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevokedCertificate {
+    serial: Vec<u8>,
+    revocation_date: SystemTime,
+    reason: Option<RevocationReason>,
+    extensions: Vec<Extension>,
+}
+
+impl RevokedCertificate {
+    /// 序列号，大端字节，不含符号位填充
+    pub fn serial_number(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn revocation_date(&self) -> SystemTime {
+        self.revocation_date
+    }
+
+    pub fn reason(&self) -> Option<RevocationReason> {
+        self.reason
+    }
+
+    /// 条目扩展，包括撤销原因
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+}
+
+/// 已解析的证书撤销列表
+///
+/// 解析时不验证签名，使用前应调用 [`verify`](Self::verify) 与 [`check_validity`](Self::check_validity)。
+#[derive(Clone, Debug)]
+pub struct CertificateRevocationList {
+    der: Vec<u8>,
+    tbs: Range<usize>,
+    version: u8,
+    issuer: Name,
+    this_update: SystemTime,
+    next_update: Option<SystemTime>,
+    revoked: Vec<RevokedCertificate>,
+    extensions: Vec<Extension>,
+    parsed: ext::Parsed,
+    /// 包括条目扩展在内存在无法处理的关键扩展
+    unhandled_critical: bool,
+    signature: Signature,
+}
+
+impl CertificateRevocationList {
+    pub fn from_der(bytes: &[u8]) -> Result<Self, X509Error> {
+        let mut outer = Reader::new(bytes);
+        let content = outer.read(der::SEQUENCE)?;
+        outer.finish()?;
+        let header = bytes.len() - content.len();
+        let mut seq = Reader::new(content);
+        let tbs_raw = seq.read_raw(der::SEQUENCE)?;
+        super::parse_signature_algorithm(&mut seq)?;
+        let signature = Signature::from_der(seq.bit_string()?).map_err(|_| X509Error::InvalidSignature)?;
+        seq.finish()?;
+
+        let mut tbs = Reader::new(tbs_raw).sequence()?;
+        // 版本可省略（v1），出现时只能为 v2
+        let version = match tbs.peek_tag() {
+            Some(der::INTEGER) if tbs.small_uint()? == 1 => 2,
+            Some(der::INTEGER) => return Err(X509Error::InvalidEncoding),
+            _ => 1,
+        };
+        super::parse_signature_algorithm(&mut tbs)?;
+        let issuer = Name::parse(tbs.read_raw(der::SEQUENCE)?)?;
+        let this_update = tbs.read_any().and_then(|(tag, t)| time::parse(tag, t))?;
+        let next_update = match tbs.peek_tag() {
+            Some(time::UTC_TIME | time::GENERALIZED_TIME) => Some(tbs.read_any().and_then(|(tag, t)| time::parse(tag, t))?),
+            _ => None,
+        };
+
+        let mut revoked = Vec::new();
+        let mut unhandled_critical = false;
+        if let Some(entries) = tbs.read_optional(der::SEQUENCE)? {
+            let mut entries = Reader::new(entries);
+            while !entries.is_empty() {
+                let mut entry = entries.sequence()?;
+                let serial = entry.uint()?.to_vec();
+                let revocation_date = entry.read_any().and_then(|(tag, t)| time::parse(tag, t))?;
+                let (extensions, parsed) = match entry.peek_tag() {
+                    Some(der::SEQUENCE) if version == 2 => ext::parse_extensions(entry.read_raw(der::SEQUENCE)?)?,
+                    Some(_) => return Err(X509Error::InvalidEncoding),
+                    None => (Vec::new(), ext::Parsed::default()),
+                };
+                entry.finish()?;
+                let reason = match parsed.reason_code {
+                    Some(code) => Some(RevocationReason::from_code(code).ok_or(X509Error::InvalidEncoding)?),
+                    None => None,
+                };
+                unhandled_critical |= parsed.unhandled_critical;
+                revoked.push(RevokedCertificate { serial, revocation_date, reason, extensions });
+            }
+        }
+        let (extensions, parsed) = match tbs.read_optional(der::context(0))? {
+            Some(_) if version != 2 => return Err(X509Error::InvalidEncoding),
+            Some(exts) => ext::parse_extensions(exts)?,
+            None => (Vec::new(), ext::Parsed::default()),
+        };
+        tbs.finish()?;
+        unhandled_critical |= parsed.unhandled_critical;
+
+        Ok(CertificateRevocationList {
+            der: bytes.to_vec(),
+            tbs: header..header + tbs_raw.len(),
+            version,
+            issuer,
+            this_update,
+            next_update,
+            revoked,
+            extensions,
+            parsed,
+            unhandled_critical,
+            signature,
+        })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, X509Error> {
+        let bytes = pem::decode(text, CRL_LABEL).ok_or(X509Error::InvalidEncoding)?;
+        Self::from_der(&bytes)
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode(CRL_LABEL, &self.der)
+    }
+
+    /// 被签名的 `TBSCertList` 编码
+    pub fn tbs_cert_list(&self) -> &[u8] {
+        &self.der[self.tbs.clone()]
+    }
+
+    /// 1 或 2
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    pub fn this_update(&self) -> SystemTime {
+        self.this_update
+    }
+
+    pub fn next_update(&self) -> Option<SystemTime> {
+        self.next_update
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// 按编码顺序的全部撤销条目
+    pub fn revoked_certificates(&self) -> &[RevokedCertificate] {
+        &self.revoked
+    }
+
+    /// CRL 扩展
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    /// CRL 序号，大端字节
+    pub fn crl_number(&self) -> Option<&[u8]> {
+        self.parsed.crl_number.as_deref()
+    }
+
+    pub fn authority_key_id(&self) -> Option<&[u8]> {
+        self.parsed.authority_key_id.as_deref()
+    }
+
+    /// 检查 `time` 是否在 thisUpdate 与 nextUpdate 之间（含两端）
+    pub fn check_validity(&self, time: SystemTime) -> Result<(), X509Error> {
+        if time < self.this_update {
+            Err(X509Error::NotYetValid)
+        } else if self.next_update.is_some_and(|next| time > next) {
+            Err(X509Error::Expired)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 验证 `issuer` 对本 CRL 的签名
+    ///
+    /// 颁发者名称必须一致；颁发者有密钥用途扩展时必须包含 cRLSign；存在无法处理的关键扩展时失败。
+    pub fn verify(&self, issuer: &Certificate) -> Result<(), X509Error> {
+        if &self.issuer != issuer.subject() {
+            return Err(X509Error::UnknownIssuer);
+        }
+        if issuer.key_usage().is_some_and(|ku| !ku.contains(KeyUsage::CRL_SIGN)) {
+            return Err(X509Error::NotCa);
+        }
+        if !issuer.public_key().verify(self.tbs_cert_list(), &self.signature) {
+            return Err(X509Error::InvalidSignature);
+        }
+        if self.unhandled_critical {
+            return Err(X509Error::UnsupportedCriticalExtension);
+        }
+        Ok(())
+    }
+
+    /// 按序列号（大端字节，可含前导零）查找撤销条目
+    pub fn find(&self, serial: &[u8]) -> Option<&RevokedCertificate> {
+        let start = serial.iter().position(|&b| b != 0).unwrap_or(serial.len());
+        self.revoked.iter().find(|r| r.serial == serial[start..])
+    }
+
+    /// 序列号为 `serial` 的证书在 `time` 时刻是否已被撤销
+    pub fn is_revoked(&self, serial: &[u8], time: SystemTime) -> bool {
+        self.find(serial).is_some_and(|r| r.revocation_date <= time && r.reason != Some(RevocationReason::RemoveFromCrl))
+    }
+
+    /// `cert` 由本 CRL 的颁发者签发，且在 `time` 时刻已被撤销
+    pub fn is_certificate_revoked(&self, cert: &Certificate, time: SystemTime) -> bool {
+        cert.issuer() == &self.issuer && self.is_revoked(cert.serial_number(), time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm2::SigningKey;
+    use crate::x509::builder::sign_der;
+    use crate::x509::tests::{INTERMEDIATE, LEAF, ROOT};
+    use crate::x509::{BasicConstraints, CertificateBuilder};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // openssl ca -gencrl -crl_lastupdate 20250601000000Z -crl_nextupdate 20250701000000Z -sigopt distid:1234567812345678
+    // 由 INTERMEDIATE 签发，撤销 LEAF（keyCompromise，2025-03-01）与序列号 0x2A（2025-04-01 12:00）
+    const INTERMEDIATE_CRL: &str = "-----BEGIN X509 CRL-----
+MIIBOTCB4AIBATAKBggqgRzPVQGDdTBBMQswCQYDVQQGEwJDTjEQMA4GA1UECgwH
+R00gVGVzdDEgMB4GA1UEAwwXR00gVGVzdCBJbnRlcm1lZGlhdGUgQ0EXDTI1MDYw
+MTAwMDAwMFoXDTI1MDcwMTAwMDAwMFowPTASAgEqFw0yNTA0MDExMjAwMDBaMCcC
+CAEjRWeJq83vFw0yNTAzMDEwMDAwMDBaMAwwCgYDVR0VBAMKAQGgLzAtMB8GA1Ud
+IwQYMBaAFIHDj9IVc67ID5ZUwzK4Fi43hLO6MAoGA1UdFAQDAgEDMAoGCCqBHM9V
+AYN1A0gAMEUCIQCdd51bCc02hDeVtpAa51vnrtDAZMXpBjGFSbkIvSO7BAIgR1LR
+/Y9DJhSV6Rd01Na7Fa8IjetBylkBwTo2EM9pHIo=
+-----END X509 CRL-----
+";
+    // 由 ROOT 签发的空 CRL
+    const ROOT_CRL: &str = "-----BEGIN X509 CRL-----
+MIHxMIGZAgEBMAoGCCqBHM9VAYN1MDkxCzAJBgNVBAYTAkNOMRAwDgYDVQQKDAdH
+TSBUZXN0MRgwFgYDVQQDDA9HTSBUZXN0IFJvb3QgQ0EXDTI1MDYwMTAwMDAwMFoX
+DTI2MDYwMTAwMDAwMFqgLzAtMB8GA1UdIwQYMBaAFLvat7dF1O8GJ3XCzRLq0ocG
+77UlMAoGA1UdFAQDAgEEMAoGCCqBHM9VAYN1A0cAMEQCIFwNXX55dqDfa9WSmnGY
+9XgtMK2f6frqrkxmPpPdMY0XAiB6BbPRorkPFPvE1NRoK2DRsamzb7FCuZ/8rFyO
+GvQPnA==
+-----END X509 CRL-----
+";
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_parse() {
+        let crl = CertificateRevocationList::from_pem(INTERMEDIATE_CRL).unwrap();
+        assert_eq!(crl.version(), 2);
+        assert_eq!(crl.issuer().to_string(), "C=CN, O=GM Test, CN=GM Test Intermediate CA");
+        assert_eq!(crl.this_update(), at(1748736000));
+        assert_eq!(crl.next_update(), Some(at(1751328000)));
+        assert_eq!(crl.crl_number(), Some(&[3][..]));
+        assert_eq!(crl.authority_key_id(), Certificate::from_pem(INTERMEDIATE).unwrap().subject_key_id());
+        assert_eq!(crl.extensions().len(), 2);
+
+        let revoked = crl.revoked_certificates();
+        assert_eq!(revoked.len(), 2);
+        assert_eq!(revoked[0].serial_number(), [0x2a]);
+        assert_eq!(revoked[0].revocation_date(), at(1743508800));
+        assert_eq!(revoked[0].reason(), None);
+        assert_eq!(revoked[1].serial_number(), [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+        assert_eq!(revoked[1].reason(), Some(RevocationReason::KeyCompromise));
+        assert_eq!(revoked[1].extensions().len(), 1);
+        assert_eq!(crl.to_pem(), INTERMEDIATE_CRL);
+
+        let empty = CertificateRevocationList::from_pem(ROOT_CRL).unwrap();
+        assert!(empty.revoked_certificates().is_empty());
+        assert_eq!(empty.crl_number(), Some(&[4][..]));
+    }
+
+    #[test]
+    fn test_verify() {
+        let root = Certificate::from_pem(ROOT).unwrap();
+        let inter = Certificate::from_pem(INTERMEDIATE).unwrap();
+        let crl = CertificateRevocationList::from_pem(INTERMEDIATE_CRL).unwrap();
+        crl.verify(&inter).unwrap();
+        assert_eq!(crl.verify(&root), Err(X509Error::UnknownIssuer));
+        CertificateRevocationList::from_pem(ROOT_CRL).unwrap().verify(&root).unwrap();
+
+        crl.check_validity(at(1750000000)).unwrap();
+        assert_eq!(crl.check_validity(at(1740000000)), Err(X509Error::NotYetValid));
+        assert_eq!(crl.check_validity(at(1760000000)), Err(X509Error::Expired));
+
+        // 篡改签名
+        let mut der = crl.as_der().to_vec();
+        let n = der.len();
+        der[n - 1] ^= 1;
+        let forged = CertificateRevocationList::from_der(&der).unwrap();
+        assert_eq!(forged.verify(&inter), Err(X509Error::InvalidSignature));
+
+        // 同名但没有 cRLSign 用途的证书签发的 CRL
+        let key = SigningKey::random();
+        let signer = CertificateBuilder::new(inter.subject().clone(), key.verifying_key(), at(0), at(2000000000))
+            .basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .key_usage(KeyUsage::KEY_CERT_SIGN)
+            .self_signed(&key)
+            .unwrap();
+        let resigned = CertificateRevocationList::from_der(&sign_der(crl.tbs_cert_list(), &key).unwrap()).unwrap();
+        assert_eq!(resigned.verify(&signer), Err(X509Error::NotCa));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let crl = CertificateRevocationList::from_pem(INTERMEDIATE_CRL).unwrap();
+        let leaf = Certificate::from_pem(LEAF).unwrap();
+        let now = at(1750000000);
+        assert!(crl.is_certificate_revoked(&leaf, now));
+        assert!(crl.is_revoked(leaf.serial_number(), now));
+        // 撤销日期之前仍然有效
+        assert!(!crl.is_revoked(leaf.serial_number(), at(1740000000)));
+        assert!(crl.is_revoked(&[0x2a], at(1743508800)));
+        assert!(!crl.is_revoked(&[0x2a], at(1743508799)));
+        // 前导零不影响查找
+        assert_eq!(crl.find(&[0, 0, 0x2a]).unwrap().serial_number(), [0x2a]);
+        assert!(crl.find(&[0x2b]).is_none());
+        assert!(!crl.is_revoked(&[0x2b], now));
+        assert!(!crl.is_revoked(&[], now));
+
+        // 其他颁发者签发的同序列号证书不受影响
+        let root_crl = CertificateRevocationList::from_pem(ROOT_CRL).unwrap();
+        assert!(!root_crl.is_certificate_revoked(&leaf, now));
+        let inter = Certificate::from_pem(INTERMEDIATE).unwrap();
+        assert!(!crl.is_certificate_revoked(&inter, now));
+    }
+}
@@ -0,0 +1,268 @@
+//! PKCS#10 证书请求（RFC 2986）
+//!
+//! 只识别 extensionRequest 属性，其余属性被忽略。
+
+use std::ops::Range;
+
+use crate::der::{self, Reader};
+use crate::pem;
+use crate::sm2::{Signature, SigningKey, VerifyingKey};
+
+use super::builder::{ExtensionSet, sign_der};
+use super::ext;
+use super::{BasicConstraints, Extension, GeneralName, KeyUsage, Name, X509Error};
+
+const CSR_LABEL: &str = "CERTIFICATE REQUEST";
+
+/// extensionRequest (1.2.840.113549.1.9.14)
+const EXTENSION_REQUEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x0e];
+
+/// 已解析的证书请求
+///
+/// 解析时不验证签名，签发前应调用 [`verify`](Self::verify)。
+#[derive(Clone, Debug)]
+pub struct CertificateRequest {
+    der: Vec<u8>,
+    info: Range<usize>,
+    subject: Name,
+    public_key: VerifyingKey,
+    extensions: Vec<Extension>,
+    parsed: ext::Parsed,
+    signature: Signature,
+}
+
+impl CertificateRequest {
+    pub fn from_der(bytes: &[u8]) -> Result<Self, X509Error> {
+        let mut outer = Reader::new(bytes);
+        let content = outer.read(der::SEQUENCE)?;
+        outer.finish()?;
+        let header = bytes.len() - content.len();
+        let mut seq = Reader::new(content);
+        let info_raw = seq.read_raw(der::SEQUENCE)?;
+        super::parse_signature_algorithm(&mut seq)?;
+        let signature = Signature::from_der(seq.bit_string()?).map_err(|_| X509Error::InvalidSignature)?;
+        seq.finish()?;
+
+        let mut info = Reader::new(info_raw).sequence()?;
+        if info.small_uint()? != 0 {
+            return Err(X509Error::InvalidEncoding);
+        }
+        let subject = Name::parse(info.read_raw(der::SEQUENCE)?)?;
+        let public_key = VerifyingKey::parse_spki(&mut info.sequence()?)?;
+        let mut attributes = Reader::new(info.read(der::context(0))?);
+        info.finish()?;
+
+        let mut requested = None;
+        while !attributes.is_empty() {
+            let mut attribute = attributes.sequence()?;
+            let id = attribute.oid()?;
+            let mut values = Reader::new(attribute.read(der::SET)?);
+            attribute.finish()?;
+            if id != EXTENSION_REQUEST {
+                continue;
+            }
+            if requested.is_some() {
+                return Err(X509Error::InvalidEncoding);
+            }
+            requested = Some(ext::parse_extensions(values.read_raw(der::SEQUENCE)?)?);
+            values.finish()?;
+        }
+        let (extensions, parsed) = requested.unwrap_or_default();
+
+        Ok(CertificateRequest {
+            der: bytes.to_vec(),
+            info: header..header + info_raw.len(),
+            subject,
+            public_key,
+            extensions,
+            parsed,
+            signature,
+        })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, X509Error> {
+        let bytes = pem::decode(text, CSR_LABEL).ok_or(X509Error::InvalidEncoding)?;
+        Self::from_der(&bytes)
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode(CSR_LABEL, &self.der)
+    }
+
+    /// 被签名的 `CertificationRequestInfo` 编码
+    pub fn request_info(&self) -> &[u8] {
+        &self.der[self.info.clone()]
+    }
+
+    pub fn subject(&self) -> &Name {
+        &self.subject
+    }
+
+    /// 请求的公钥，使用默认ID
+    pub fn public_key(&self) -> &VerifyingKey {
+        &self.public_key
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// extensionRequest 中的全部扩展
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn key_usage(&self) -> Option<KeyUsage> {
+        self.parsed.key_usage
+    }
+
+    pub fn basic_constraints(&self) -> Option<BasicConstraints> {
+        self.parsed.basic_constraints
+    }
+
+    pub fn extended_key_usage(&self) -> Option<&[String]> {
+        self.parsed.ext_key_usage.as_deref()
+    }
+
+    pub fn subject_alt_names(&self) -> &[GeneralName] {
+        &self.parsed.subject_alt_names
+    }
+
+    /// 用请求中的公钥验证自签名，证明申请者持有私钥
+    pub fn verify(&self) -> Result<(), X509Error> {
+        if self.public_key.verify(self.request_info(), &self.signature) { Ok(()) } else { Err(X509Error::InvalidSignature) }
+    }
+}
+
+/// 证书请求构造器
+#[derive(Clone, Debug)]
+pub struct CsrBuilder {
+    subject: Name,
+    extensions: ExtensionSet,
+}
+
+impl CsrBuilder {
+    pub fn new(subject: Name) -> Self {
+        CsrBuilder { subject, extensions: ExtensionSet::default() }
+    }
+
+    pub fn key_usage(mut self, usage: KeyUsage) -> Self {
+        self.extensions.key_usage = Some(usage);
+        self
+    }
+
+    /// 扩展密钥用途，见 [`eku`](super::eku)
+    pub fn extended_key_usage(mut self, oids: &[&str]) -> Self {
+        self.extensions.ext_key_usage = oids.iter().map(|o| o.to_string()).collect();
+        self
+    }
+
+    pub fn basic_constraints(mut self, constraints: BasicConstraints) -> Self {
+        self.extensions.basic_constraints = Some(constraints);
+        self
+    }
+
+    /// 追加一个主体备用名
+    pub fn subject_alt_name(mut self, name: GeneralName) -> Self {
+        self.extensions.subject_alt_names.push(name);
+        self
+    }
+
+    /// 用 `key` 签名，请求中的公钥即 `key` 的公钥
+    pub fn sign(self, key: &SigningKey) -> Result<CertificateRequest, X509Error> {
+        let extensions = self.extensions.encode(&self.subject)?;
+        let attributes = if extensions.is_empty() {
+            Vec::new()
+        } else {
+            let extensions = der::sequence(&extensions.iter().map(Vec::as_slice).collect::<Vec<_>>());
+            der::sequence(&[&der::oid(EXTENSION_REQUEST), &der::constructed(der::SET, &[&extensions])])
+        };
+        let info = der::sequence(&[
+            &der::small_uint(0),
+            self.subject.as_der(),
+            &key.verifying_key().to_public_key_der(),
+            &der::constructed(der::context(0), &[&attributes]),
+        ]);
+        CertificateRequest::from_der(&sign_der(&info, key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x509::{CertificateBuilder, attr, eku};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // openssl req -new -addext "subjectAltName=DNS:csr.example.cn,IP:10.0.0.1" \
+    //     -addext "keyUsage=critical,digitalSignature" -sigopt distid:1234567812345678
+    const OPENSSL_CSR: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIIBNTCB3AIBADA4MQswCQYDVQQGEwJDTjEQMA4GA1UECgwHR00gVGVzdDEXMBUG
+A1UEAwwOY3NyLmV4YW1wbGUuY24wWTATBgcqhkjOPQIBBggqgRzPVQGCLQNCAARz
+7hpEvQ1CvPYNdaH832ymSIsr5SbTJ2u8d+eWmRhVH77W+0p70F1UgC1E8llwF8MT
+PS2VeR3D16TIqXDQtjvpoEIwQAYJKoZIhvcNAQkOMTMwMTAfBgNVHREEGDAWgg5j
+c3IuZXhhbXBsZS5jbocECgAAATAOBgNVHQ8BAf8EBAMCB4AwCgYIKoEcz1UBg3UD
+SAAwRQIgYpy5X2zqSoG2ARZfzYqvf3emgy8N/FRR4YPN5OQy9z8CIQDZ8OJlwwUq
+oC3ykSc58zXQGAdm0Aq9MaZM27JV2jklhA==
+-----END CERTIFICATE REQUEST-----
+";
+
+    #[test]
+    fn test_parse_openssl() {
+        let csr = CertificateRequest::from_pem(OPENSSL_CSR).unwrap();
+        csr.verify().unwrap();
+        assert_eq!(csr.subject().to_string(), "C=CN, O=GM Test, CN=csr.example.cn");
+        assert_eq!(csr.key_usage(), Some(KeyUsage::DIGITAL_SIGNATURE));
+        assert_eq!(
+            csr.subject_alt_names(),
+            [GeneralName::Dns("csr.example.cn".into()), GeneralName::Ip("10.0.0.1".parse().unwrap())]
+        );
+        assert_eq!(csr.to_pem(), OPENSSL_CSR);
+    }
+
+    #[test]
+    fn test_build_and_issue() {
+        let key = SigningKey::random();
+        let subject = Name::new(&[(attr::COUNTRY, "CN"), (attr::COMMON_NAME, "client")]).unwrap();
+        let csr = CsrBuilder::new(subject.clone())
+            .key_usage(KeyUsage::DIGITAL_SIGNATURE)
+            .extended_key_usage(&[eku::CLIENT_AUTH])
+            .subject_alt_name(GeneralName::Email("client@example.cn".into()))
+            .sign(&key)
+            .unwrap();
+        csr.verify().unwrap();
+        assert_eq!(CertificateRequest::from_pem(&csr.to_pem()).unwrap().as_der(), csr.as_der());
+        assert_eq!(csr.subject(), &subject);
+        assert_eq!(csr.public_key(), key.verifying_key());
+        assert_eq!(csr.extensions().len(), 3);
+
+        let ca_key = SigningKey::random();
+        let (start, end) = (UNIX_EPOCH, UNIX_EPOCH + Duration::from_secs(86400));
+        let ca = CertificateBuilder::new(Name::new(&[(attr::COMMON_NAME, "CA")]).unwrap(), ca_key.verifying_key(), start, end)
+            .basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .self_signed(&ca_key)
+            .unwrap();
+        let cert = CertificateBuilder::from_request(&csr, start, end).unwrap().sign(&ca, &ca_key).unwrap();
+        cert.verify_issued_by(&ca).unwrap();
+        assert_eq!(cert.subject(), &subject);
+        assert_eq!(cert.key_usage(), Some(KeyUsage::DIGITAL_SIGNATURE));
+        assert_eq!(cert.extended_key_usage().unwrap(), [eku::CLIENT_AUTH]);
+        assert_eq!(cert.subject_alt_names(), csr.subject_alt_names());
+
+        // 无扩展时属性为空
+        let bare = CsrBuilder::new(subject).sign(&key).unwrap();
+        assert!(bare.extensions().is_empty());
+        assert_eq!(bare.key_usage(), None);
+
+        // 篡改后签名无效
+        let mut der = csr.as_der().to_vec();
+        let pos = der.windows(6).position(|w| w == b"client").unwrap();
+        der[pos] = b'C';
+        let forged = CertificateRequest::from_der(&der).unwrap();
+        assert_eq!(forged.verify(), Err(X509Error::InvalidSignature));
+        assert!(CertificateBuilder::from_request(&forged, start, end).is_err());
+    }
+}
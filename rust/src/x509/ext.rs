@@ -0,0 +1,404 @@
+//! 证书扩展（RFC 5280 4.2）
+
+use std::fmt;
+use std::net::IpAddr;
+use std::ops::BitOr;
+
+use crate::der::{self, DerError, Reader};
+
+use super::name::Name;
+
+/// 扩展 OID 的内容字节
+pub(crate) mod id {
+    pub(crate) const SUBJECT_KEY_ID: &[u8] = &[0x55, 0x1d, 0x0e];
+    pub(crate) const KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+    pub(crate) const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+    pub(crate) const BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+    pub(crate) const CRL_NUMBER: &[u8] = &[0x55, 0x1d, 0x14];
+    pub(crate) const CRL_REASON: &[u8] = &[0x55, 0x1d, 0x15];
+    pub(crate) const AUTHORITY_KEY_ID: &[u8] = &[0x55, 0x1d, 0x23];
+    pub(crate) const EXT_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+}
+
+/// 扩展密钥用途的 OID
+pub mod eku {
+    pub const SERVER_AUTH: &str = "1.3.6.1.5.5.7.3.1";
+    pub const CLIENT_AUTH: &str = "1.3.6.1.5.5.7.3.2";
+    pub const CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+    pub const EMAIL_PROTECTION: &str = "1.3.6.1.5.5.7.3.4";
+    pub const TIME_STAMPING: &str = "1.3.6.1.5.5.7.3.8";
+    pub const OCSP_SIGNING: &str = "1.3.6.1.5.5.7.3.9";
+}
+
+/// 原始扩展
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Extension {
+    /// 点分形式的 OID
+    pub oid: String,
+    pub critical: bool,
+    /// extnValue 的内容
+    pub value: Vec<u8>,
+}
+
+/// 密钥用途，位序与 RFC 5280 的 KeyUsage 一致
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct KeyUsage(u16);
+
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(1 << 0);
+    pub const NON_REPUDIATION: KeyUsage = KeyUsage(1 << 1);
+    pub const KEY_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 2);
+    pub const DATA_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 3);
+    pub const KEY_AGREEMENT: KeyUsage = KeyUsage(1 << 4);
+    pub const KEY_CERT_SIGN: KeyUsage = KeyUsage(1 << 5);
+    pub const CRL_SIGN: KeyUsage = KeyUsage(1 << 6);
+    pub const ENCIPHER_ONLY: KeyUsage = KeyUsage(1 << 7);
+    pub const DECIPHER_ONLY: KeyUsage = KeyUsage(1 << 8);
+
+    const NAMES: [&'static str; 9] = [
+        "digitalSignature",
+        "nonRepudiation",
+        "keyEncipherment",
+        "dataEncipherment",
+        "keyAgreement",
+        "keyCertSign",
+        "cRLSign",
+        "encipherOnly",
+        "decipherOnly",
+    ];
+
+    /// 第 n 位对应 KeyUsage 的第 n 个命名位
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u16) -> Self {
+        KeyUsage(bits & 0x1ff)
+    }
+
+    pub fn contains(&self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// 编码为命名位串，去掉末尾的零位
+    pub(crate) fn to_der(self) -> Vec<u8> {
+        if self.0 == 0 {
+            return der::tlv(der::BIT_STRING, &[0]);
+        }
+        let high = 15 - self.0.leading_zeros() as usize;
+        let mut content = vec![(7 - high % 8) as u8];
+        content.extend((0..=high / 8).map(|i| ((self.0 >> (8 * i)) as u8).reverse_bits()));
+        der::tlv(der::BIT_STRING, &content)
+    }
+
+    fn parse(value: &[u8]) -> Result<Self, DerError> {
+        let mut r = Reader::new(value);
+        let content = r.read(der::BIT_STRING)?;
+        r.finish()?;
+        let (&unused, bytes) = content.split_first().ok_or(DerError)?;
+        if unused > 7 || (bytes.is_empty() && unused != 0) || bytes.len() > 2 {
+            return Err(DerError);
+        }
+        // 命名位从字节最高位开始编号
+        let bits = bytes.iter().enumerate().fold(0u16, |acc, (i, &b)| acc | (b.reverse_bits() as u16) << (8 * i));
+        Ok(KeyUsage::from_bits(bits))
+    }
+}
+
+impl BitOr for KeyUsage {
+    type Output = KeyUsage;
+
+    fn bitor(self, rhs: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Debug for KeyUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = (0..9).filter(|i| self.0 & (1 << i) != 0).map(|i| Self::NAMES[i]).collect();
+        write!(f, "KeyUsage({})", names.join(" | "))
+    }
+}
+
+/// 基本约束
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BasicConstraints {
+    pub ca: bool,
+    /// 此 CA 之下允许的中间 CA 数量上限
+    pub path_len: Option<u32>,
+}
+
+impl BasicConstraints {
+    pub(crate) fn to_der(self) -> Vec<u8> {
+        let ca = if self.ca { der::boolean(true) } else { Vec::new() };
+        let path_len = self.path_len.map_or(Vec::new(), |n| der::small_uint(n as u64));
+        der::sequence(&[&ca, &path_len])
+    }
+
+    fn parse(value: &[u8]) -> Result<Self, DerError> {
+        let mut outer = Reader::new(value);
+        let mut seq = outer.sequence()?;
+        outer.finish()?;
+        // DER 中取默认值 FALSE 的字段必须省略
+        let ca = match seq.peek_tag() {
+            Some(der::BOOLEAN) if seq.boolean()? => true,
+            Some(der::BOOLEAN) => return Err(DerError),
+            _ => false,
+        };
+        let path_len = match seq.peek_tag() {
+            Some(der::INTEGER) => Some(u32::try_from(seq.small_uint()?).map_err(|_| DerError)?),
+            _ => None,
+        };
+        seq.finish()?;
+        Ok(BasicConstraints { ca, path_len })
+    }
+}
+
+/// GeneralName 中常用的几种
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GeneralName {
+    Email(String),
+    Dns(String),
+    DirectoryName(Name),
+    Uri(String),
+    Ip(IpAddr),
+    /// 其他类型，保存标签与内容
+    Other(u8, Vec<u8>),
+}
+
+impl GeneralName {
+    pub(crate) fn to_der(&self) -> Vec<u8> {
+        match self {
+            GeneralName::Email(s) => der::tlv(0x81, s.as_bytes()),
+            GeneralName::Dns(s) => der::tlv(0x82, s.as_bytes()),
+            GeneralName::Uri(s) => der::tlv(0x86, s.as_bytes()),
+            GeneralName::DirectoryName(name) => der::tlv(0xa4, name.as_der()),
+            GeneralName::Ip(IpAddr::V4(ip)) => der::tlv(0x87, &ip.octets()),
+            GeneralName::Ip(IpAddr::V6(ip)) => der::tlv(0x87, &ip.octets()),
+            GeneralName::Other(tag, value) => der::tlv(*tag, value),
+        }
+    }
+
+    pub(crate) fn parse(tag: u8, value: &[u8]) -> Result<Self, DerError> {
+        let ia5 = || if value.is_ascii() { Ok(value.iter().map(|&b| b as char).collect::<String>()) } else { Err(DerError) };
+        Ok(match tag {
+            0x81 => GeneralName::Email(ia5()?),
+            0x82 => GeneralName::Dns(ia5()?),
+            0x86 => GeneralName::Uri(ia5()?),
+            // [4] 为显式标签
+            0xa4 => {
+                let mut r = Reader::new(value);
+                let name = Name::parse(r.read_raw(der::SEQUENCE)?)?;
+                r.finish()?;
+                GeneralName::DirectoryName(name)
+            }
+            0x87 => match value.len() {
+                4 => GeneralName::Ip(IpAddr::from(<[u8; 4]>::try_from(value).unwrap())),
+                16 => GeneralName::Ip(IpAddr::from(<[u8; 16]>::try_from(value).unwrap())),
+                _ => return Err(DerError),
+            },
+            _ => GeneralName::Other(tag, value.to_vec()),
+        })
+    }
+}
+
+/// 编码一个 Extension
+pub(crate) fn encode_extension(id: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+    let critical = if critical { der::boolean(true) } else { Vec::new() };
+    der::sequence(&[&der::oid(id), &critical, &der::octet_string(value)])
+}
+
+pub(crate) fn encode_general_names(names: &[GeneralName]) -> Vec<u8> {
+    der::sequence(&names.iter().map(GeneralName::to_der).collect::<Vec<_>>().iter().map(Vec::as_slice).collect::<Vec<_>>())
+}
+
+/// OID 不合法时返回 `None`
+pub(crate) fn encode_ext_key_usage(oids: &[String]) -> Option<Vec<u8>> {
+    let oids = oids.iter().map(|o| der::oid_from_str(o).map(|o| der::oid(&o))).collect::<Option<Vec<_>>>()?;
+    Some(der::sequence(&oids.iter().map(Vec::as_slice).collect::<Vec<_>>()))
+}
+
+fn parse_general_names(value: &[u8]) -> Result<Vec<GeneralName>, DerError> {
+    let mut outer = Reader::new(value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut names = Vec::new();
+    while !seq.is_empty() {
+        let (tag, content) = seq.read_any()?;
+        names.push(GeneralName::parse(tag, content)?);
+    }
+    Ok(names)
+}
+
+fn parse_ext_key_usage(value: &[u8]) -> Result<Vec<String>, DerError> {
+    let mut outer = Reader::new(value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut oids = Vec::new();
+    while !seq.is_empty() {
+        oids.push(der::oid_to_string(seq.oid()?)?);
+    }
+    if oids.is_empty() {
+        return Err(DerError);
+    }
+    Ok(oids)
+}
+
+fn parse_key_id(value: &[u8]) -> Result<Vec<u8>, DerError> {
+    let mut r = Reader::new(value);
+    let id = r.octet_string()?;
+    r.finish()?;
+    Ok(id.to_vec())
+}
+
+fn parse_crl_number(value: &[u8]) -> Result<Vec<u8>, DerError> {
+    let mut r = Reader::new(value);
+    let number = r.uint()?;
+    r.finish()?;
+    Ok(number.to_vec())
+}
+
+/// CRLReason 为单字节 ENUMERATED
+fn parse_reason_code(value: &[u8]) -> Result<u8, DerError> {
+    let mut r = Reader::new(value);
+    let code = match r.read(der::ENUMERATED)? {
+        &[code] if code < 0x80 => code,
+        _ => return Err(DerError),
+    };
+    r.finish()?;
+    Ok(code)
+}
+
+/// 只取 AuthorityKeyIdentifier 中的 keyIdentifier [0]
+fn parse_authority_key_id(value: &[u8]) -> Result<Option<Vec<u8>>, DerError> {
+    let mut outer = Reader::new(value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let id = seq.read_optional(0x80)?.map(|id| id.to_vec());
+    seq.read_optional(der::context(1))?;
+    seq.read_optional(0x82)?;
+    seq.finish()?;
+    Ok(id)
+}
+
+/// 解析出的常用扩展
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Parsed {
+    pub(crate) key_usage: Option<KeyUsage>,
+    pub(crate) basic_constraints: Option<BasicConstraints>,
+    pub(crate) ext_key_usage: Option<Vec<String>>,
+    pub(crate) subject_alt_names: Vec<GeneralName>,
+    pub(crate) subject_key_id: Option<Vec<u8>>,
+    pub(crate) authority_key_id: Option<Vec<u8>>,
+    /// CRL 序号，大端字节
+    pub(crate) crl_number: Option<Vec<u8>>,
+    /// CRL 条目的撤销原因代码
+    pub(crate) reason_code: Option<u8>,
+    /// 存在无法识别的关键扩展
+    pub(crate) unhandled_critical: bool,
+}
+
+/// 解析 Extensions 的内容，同一扩展不得出现两次
+pub(crate) fn parse_extensions(content: &[u8]) -> Result<(Vec<Extension>, Parsed), DerError> {
+    let mut outer = Reader::new(content);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut extensions: Vec<Extension> = Vec::new();
+    let mut parsed = Parsed::default();
+    while !seq.is_empty() {
+        let mut ext = seq.sequence()?;
+        let id = ext.oid()?;
+        let critical = match ext.peek_tag() {
+            Some(der::BOOLEAN) if ext.boolean()? => true,
+            Some(der::BOOLEAN) => return Err(DerError),
+            _ => false,
+        };
+        let value = ext.octet_string()?;
+        ext.finish()?;
+
+        let oid = der::oid_to_string(id)?;
+        if extensions.iter().any(|e| e.oid == oid) {
+            return Err(DerError);
+        }
+        match id {
+            id::KEY_USAGE => parsed.key_usage = Some(KeyUsage::parse(value)?),
+            id::BASIC_CONSTRAINTS => parsed.basic_constraints = Some(BasicConstraints::parse(value)?),
+            id::EXT_KEY_USAGE => parsed.ext_key_usage = Some(parse_ext_key_usage(value)?),
+            id::SUBJECT_ALT_NAME => parsed.subject_alt_names = parse_general_names(value)?,
+            id::SUBJECT_KEY_ID => parsed.subject_key_id = Some(parse_key_id(value)?),
+            id::AUTHORITY_KEY_ID => parsed.authority_key_id = parse_authority_key_id(value)?,
+            id::CRL_NUMBER => parsed.crl_number = Some(parse_crl_number(value)?),
+            id::CRL_REASON => parsed.reason_code = Some(parse_reason_code(value)?),
+            _ => parsed.unhandled_critical |= critical,
+        }
+        extensions.push(Extension { oid, critical, value: value.to_vec() });
+    }
+    Ok((extensions, parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_usage() {
+        // digitalSignature | keyEncipherment，未用位5
+        let ku = KeyUsage::parse(&[0x03, 0x02, 0x05, 0xa0]).unwrap();
+        assert_eq!(ku, KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_ENCIPHERMENT);
+        assert!(ku.contains(KeyUsage::DIGITAL_SIGNATURE));
+        assert!(!ku.contains(KeyUsage::KEY_CERT_SIGN));
+        assert_eq!(format!("{:?}", ku), "KeyUsage(digitalSignature | keyEncipherment)");
+
+        assert_eq!(ku.to_der(), [0x03, 0x02, 0x05, 0xa0]);
+
+        let ku = KeyUsage::parse(&[0x03, 0x03, 0x07, 0x06, 0x80]).unwrap();
+        assert_eq!(ku, KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN | KeyUsage::DECIPHER_ONLY);
+        assert_eq!(ku.to_der(), [0x03, 0x03, 0x07, 0x06, 0x80]);
+        assert_eq!(KeyUsage::parse(&KeyUsage::default().to_der()).unwrap(), KeyUsage::default());
+        assert!(KeyUsage::parse(&[0x03, 0x01, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_basic_constraints() {
+        let parse = |parts: &[&[u8]]| BasicConstraints::parse(&der::sequence(parts));
+        assert_eq!(parse(&[]).unwrap(), BasicConstraints { ca: false, path_len: None });
+        assert_eq!(parse(&[&der::boolean(true), &der::small_uint(0)]).unwrap(), BasicConstraints { ca: true, path_len: Some(0) });
+        for bc in [BasicConstraints::default(), BasicConstraints { ca: true, path_len: None }, BasicConstraints { ca: true, path_len: Some(3) }] {
+            assert_eq!(BasicConstraints::parse(&bc.to_der()).unwrap(), bc);
+        }
+        // 显式编码默认值
+        assert!(parse(&[&der::boolean(false)]).is_err());
+    }
+
+    #[test]
+    fn test_general_names() {
+        let value = der::sequence(&[&der::tlv(0x82, b"a.cn"), &der::tlv(0x87, &[10, 0, 0, 1]), &der::tlv(0x88, &[1])]);
+        let names = parse_general_names(&value).unwrap();
+        assert_eq!(names[0], GeneralName::Dns("a.cn".into()));
+        assert_eq!(names[1], GeneralName::Ip("10.0.0.1".parse().unwrap()));
+        assert_eq!(names[2], GeneralName::Other(0x88, vec![1]));
+        assert!(parse_general_names(&der::sequence(&[&der::tlv(0x87, &[1, 2, 3])])).is_err());
+        assert_eq!(encode_general_names(&names), value);
+
+        let v6 = vec![GeneralName::Ip("::1".parse().unwrap()), GeneralName::Uri("https://a.cn/".into())];
+        assert_eq!(parse_general_names(&encode_general_names(&v6)).unwrap(), v6);
+    }
+
+    #[test]
+    fn test_duplicate_and_critical() {
+        let ext = |id: &[u8], critical: bool| encode_extension(id, critical, &der::sequence(&[]));
+        let unknown: &[u8] = &[0x55, 0x1d, 0x1e];
+        let (exts, parsed) = parse_extensions(&der::sequence(&[&ext(id::BASIC_CONSTRAINTS, true), &ext(unknown, false)])).unwrap();
+        assert_eq!(exts[0].oid, "2.5.29.19");
+        assert!(exts[0].critical);
+        assert!(!parsed.unhandled_critical);
+
+        let (_, parsed) = parse_extensions(&der::sequence(&[&ext(unknown, true)])).unwrap();
+        assert!(parsed.unhandled_critical);
+        assert!(parse_extensions(&der::sequence(&[&ext(unknown, false), &ext(unknown, false)])).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! X.501 可分辨名称（Distinguished Name）
+
+use std::fmt;
+
+use crate::der::{self, DerError, Reader};
+
+use super::X509Error;
+
+/// 常用属性类型的 OID
+pub mod attr {
+    pub const COUNTRY: &str = "2.5.4.6";
+    pub const STATE_OR_PROVINCE: &str = "2.5.4.8";
+    pub const LOCALITY: &str = "2.5.4.7";
+    pub const ORGANIZATION: &str = "2.5.4.10";
+    pub const ORGANIZATIONAL_UNIT: &str = "2.5.4.11";
+    pub const COMMON_NAME: &str = "2.5.4.3";
+    pub const SERIAL_NUMBER: &str = "2.5.4.5";
+    pub const EMAIL_ADDRESS: &str = "1.2.840.113549.1.9.1";
+}
+
+/// 显示用的简称，与 OpenSSL 一致
+const SHORT_NAMES: [(&str, &str); 8] = [
+    (attr::COUNTRY, "C"),
+    (attr::STATE_OR_PROVINCE, "ST"),
+    (attr::LOCALITY, "L"),
+    (attr::ORGANIZATION, "O"),
+    (attr::ORGANIZATIONAL_UNIT, "OU"),
+    (attr::COMMON_NAME, "CN"),
+    (attr::SERIAL_NUMBER, "serialNumber"),
+    (attr::EMAIL_ADDRESS, "emailAddress"),
+];
+
+/// 可分辨名称，按编码顺序保存属性
+///
+/// 比较时按 DER 编码逐字节比较。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name {
+    der: Vec<u8>,
+    attributes: Vec<(String, String)>,
+}
+
+impl Name {
+    /// 由 (OID, 值) 依次构造，每个属性单独作为一个 RDN
+    ///
+    /// 国家与序列号编码为 PrintableString，邮件地址为 IA5String，其余为 UTF8String。
+    pub fn new(attributes: &[(&str, &str)]) -> Result<Self, X509Error> {
+        let mut rdns = Vec::new();
+        for &(oid, value) in attributes {
+            let id = der::oid_from_str(oid).ok_or(X509Error::InvalidEncoding)?;
+            let tag = match oid {
+                attr::COUNTRY | attr::SERIAL_NUMBER if value.bytes().all(is_printable) => 0x13,
+                attr::COUNTRY => return Err(X509Error::InvalidEncoding),
+                attr::EMAIL_ADDRESS if value.is_ascii() => 0x16,
+                attr::EMAIL_ADDRESS => return Err(X509Error::InvalidEncoding),
+                _ => 0x0c,
+            };
+            let atv = der::sequence(&[&der::oid(&id), &der::tlv(tag, value.as_bytes())]);
+            rdns.push(der::constructed(der::SET, &[&atv]));
+        }
+        let raw = der::sequence(&rdns.iter().map(Vec::as_slice).collect::<Vec<_>>());
+        Ok(Self::parse(&raw)?)
+    }
+
+    /// 解析完整的 `Name` 编码（含 SEQUENCE 头）
+    pub(crate) fn parse(raw: &[u8]) -> Result<Self, DerError> {
+        let mut outer = Reader::new(raw);
+        let mut rdns = outer.sequence()?;
+        outer.finish()?;
+        let mut attributes = Vec::new();
+        while !rdns.is_empty() {
+            let mut set = Reader::new(rdns.read(der::SET)?);
+            if set.is_empty() {
+                return Err(DerError);
+            }
+            while !set.is_empty() {
+                let mut atv = set.sequence()?;
+                let oid = der::oid_to_string(atv.oid()?)?;
+                let (tag, value) = atv.read_any()?;
+                atv.finish()?;
+                attributes.push((oid, decode_string(tag, value)?));
+            }
+        }
+        Ok(Name { der: raw.to_vec(), attributes })
+    }
+
+    /// DER 编码
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// 按编码顺序遍历 (OID, 值)
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().map(|(oid, value)| (oid.as_str(), value.as_str()))
+    }
+
+    /// 第一个类型为 `oid` 的属性值
+    pub fn get(&self, oid: &str) -> Option<&str> {
+        self.attributes().find(|(t, _)| *t == oid).map(|(_, v)| v)
+    }
+
+    pub fn common_name(&self) -> Option<&str> {
+        self.get(attr::COMMON_NAME)
+    }
+}
+
+/// 按编码顺序输出 `C=CN, O=..., CN=...`
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (oid, value)) in self.attributes().enumerate() {
+            let key = SHORT_NAMES.iter().find(|(o, _)| *o == oid).map_or(oid, |(_, short)| short);
+            write!(f, "{}{}={}", if i == 0 { "" } else { ", " }, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// PrintableString 允许的字符
+fn is_printable(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b" '()+,-./:=?".contains(&b)
+}
+
+/// 解码 DirectoryString 等字符串类型
+pub(crate) fn decode_string(tag: u8, value: &[u8]) -> Result<String, DerError> {
+    match tag {
+        // UTF8String
+        0x0c => String::from_utf8(value.to_vec()).map_err(|_| DerError),
+        // PrintableString、IA5String
+        0x13 | 0x16 if value.is_ascii() => Ok(value.iter().map(|&b| b as char).collect()),
+        // TeletexString，按 Latin-1 处理
+        0x14 => Ok(value.iter().map(|&b| b as char).collect()),
+        // BMPString
+        0x1e if value.len().is_multiple_of(2) => {
+            let units: Vec<u16> = value.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16(&units).map_err(|_| DerError)
+        }
+        // UniversalString
+        0x1c if value.len().is_multiple_of(4) => value
+            .chunks(4)
+            .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])).ok_or(DerError))
+            .collect(),
+        _ => Err(DerError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        // C=CN, O=测试 (UTF8String), CN=Root (BMPString)
+        let c = der::sequence(&[&der::oid(&[0x55, 0x04, 0x06]), &der::tlv(0x13, b"CN")]);
+        let o = der::sequence(&[&der::oid(&[0x55, 0x04, 0x0a]), &der::tlv(0x0c, "测试".as_bytes())]);
+        let cn = der::sequence(&[&der::oid(&[0x55, 0x04, 0x03]), &der::tlv(0x1e, &[0, b'R', 0, b'o', 0, b'o', 0, b't'])]);
+        let raw = der::sequence(&[&der::constructed(der::SET, &[&c]), &der::constructed(der::SET, &[&o]), &der::constructed(der::SET, &[&cn])]);
+
+        let name = Name::parse(&raw).unwrap();
+        assert_eq!(name.to_string(), "C=CN, O=测试, CN=Root");
+        assert_eq!(name.common_name(), Some("Root"));
+        assert_eq!(name.get(attr::COUNTRY), Some("CN"));
+        assert_eq!(name.get(attr::LOCALITY), None);
+        assert_eq!(name.as_der(), raw);
+    }
+
+    #[test]
+    fn test_new() {
+        let name = Name::new(&[(attr::COUNTRY, "CN"), (attr::ORGANIZATION, "测试"), (attr::EMAIL_ADDRESS, "a@b.cn"), (attr::COMMON_NAME, "Root")]).unwrap();
+        assert_eq!(name.to_string(), "C=CN, O=测试, emailAddress=a@b.cn, CN=Root");
+        // C 为 PrintableString，O 为 UTF8String
+        assert!(name.as_der().windows(4).any(|w| w == [0x13, 0x02, b'C', b'N']));
+        assert!(name.as_der().windows(2).any(|w| w == [0x0c, 0x06]));
+
+        assert_eq!(Name::new(&[]).unwrap().as_der(), [0x30, 0x00]);
+        assert!(Name::new(&[("2.5.4", "x")]).is_ok());
+        assert!(Name::new(&[("x", "y")]).is_err());
+        assert!(Name::new(&[(attr::COUNTRY, "中国")]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        // 空 RDN、非 ASCII 的 PrintableString、未知字符串类型
+        assert!(Name::parse(&der::sequence(&[&der::constructed(der::SET, &[])])).is_err());
+        let bad = der::sequence(&[&der::oid(&[0x55, 0x04, 0x03]), &der::tlv(0x13, &[0xe4])]);
+        assert!(Name::parse(&der::sequence(&[&der::constructed(der::SET, &[&bad])])).is_err());
+        let bad = der::sequence(&[&der::oid(&[0x55, 0x04, 0x03]), &der::tlv(der::INTEGER, &[1])]);
+        assert!(Name::parse(&der::sequence(&[&der::constructed(der::SET, &[&bad])])).is_err());
+        assert!(decode_string(0x1e, &[0xd8, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! ASN.1 UTCTime / GeneralizedTime 与 `SystemTime` 的转换
+//!
+//! 按 RFC 5280 只接受以 `Z` 结尾、精确到秒的 UTC 时间。
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::der::{self, DerError};
+
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+
+/// 解析时间元素的内容
+pub(crate) fn parse(tag: u8, content: &[u8]) -> Result<SystemTime, DerError> {
+    let (year, rest) = match tag {
+        // YYMMDDHHMMSSZ，50-99 表示 19xx
+        UTC_TIME if content.len() == 13 => {
+            let yy = digits(&content[..2])?;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, &content[2..])
+        }
+        // YYYYMMDDHHMMSSZ
+        GENERALIZED_TIME if content.len() == 15 => (digits(&content[..4])?, &content[4..]),
+        _ => return Err(DerError),
+    };
+    if rest[10] != b'Z' {
+        return Err(DerError);
+    }
+    let month = digits(&rest[0..2])?;
+    let day = digits(&rest[2..4])?;
+    let hour = digits(&rest[4..6])?;
+    let minute = digits(&rest[6..8])?;
+    let second = digits(&rest[8..10])?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) || hour > 23 || minute > 59 || second > 59 {
+        return Err(DerError);
+    }
+
+    let secs = days_from_civil(year as i64, month, day) * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+    Ok(if secs >= 0 {
+        UNIX_EPOCH + Duration::from_secs(secs as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    })
+}
+
+/// 编码为时间元素：1950-2049 年用 UTCTime，其余用 GeneralizedTime，秒以下被截去
+pub(crate) fn encode(time: SystemTime) -> Vec<u8> {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+    };
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    let (year, month, day) = civil_from_days(days);
+    let hms = format!("{:02}{:02}{:02}{:02}{:02}Z", month, day, rem / 3600, rem / 60 % 60, rem % 60);
+    if (1950..2050).contains(&year) {
+        der::tlv(UTC_TIME, format!("{:02}{}", year % 100, hms).as_bytes())
+    } else {
+        der::tlv(GENERALIZED_TIME, format!("{:04}{}", year, hms).as_bytes())
+    }
+}
+
+fn digits(bytes: &[u8]) -> Result<u32, DerError> {
+    bytes.iter().try_fold(0u32, |acc, &b| if b.is_ascii_digit() { Ok(acc * 10 + (b - b'0') as u32) } else { Err(DerError) })
+}
+
+fn is_leap(year: u32) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 公历日期到 1970-01-01 的天数（Howard Hinnant 算法）
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// `days_from_civil` 的逆运算
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unix(t: SystemTime) -> i64 {
+        match t.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(unix(parse(UTC_TIME, b"700101000000Z").unwrap()), 0);
+        assert_eq!(unix(parse(UTC_TIME, b"250101000000Z").unwrap()), 1735689600);
+        assert_eq!(unix(parse(UTC_TIME, b"491231235959Z").unwrap()), 2524607999);
+        assert_eq!(unix(parse(UTC_TIME, b"500101000000Z").unwrap()), -631152000);
+        assert_eq!(unix(parse(GENERALIZED_TIME, b"20500101000000Z").unwrap()), 2524608000);
+        assert_eq!(unix(parse(GENERALIZED_TIME, b"20240229120000Z").unwrap()), 1709208000);
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        for bad in [&b"250101000000"[..], b"2501010000Z", b"250101000000+0800", b"250230000000Z", b"251301000000Z", b"250101240000Z"] {
+            assert!(parse(UTC_TIME, bad).is_err());
+        }
+        assert!(parse(GENERALIZED_TIME, b"20230229000000Z").is_err());
+        assert!(parse(GENERALIZED_TIME, b"20250101000000.5Z").is_err());
+        assert!(parse(UTC_TIME, b"20250101000000Z").is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let cases: [(&[u8], i64); 5] = [
+            (b"\x17\x0d700101000000Z", 0),
+            (b"\x17\x0d240229120000Z", 1709208000),
+            (b"\x17\x0d491231235959Z", 2524607999),
+            (b"\x18\x0f20500101000000Z", 2524608000),
+            (b"\x18\x0f19491231235959Z", -631152001),
+        ];
+        for (encoded, secs) in cases {
+            let time = if secs >= 0 { UNIX_EPOCH + Duration::from_secs(secs as u64) } else { UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()) };
+            assert_eq!(encode(time), encoded);
+            assert_eq!(unix(parse(encoded[0], &encoded[2..]).unwrap()), secs);
+        }
+        // 秒以下截去
+        assert_eq!(encode(UNIX_EPOCH + Duration::from_millis(1999)), b"\x17\x0d700101000001Z");
+    }
+}